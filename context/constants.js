@@ -12,5 +12,8 @@ export const server = new SorobanRpc.Server(
 
 export const contract = new Contract(process.env.NEXT_PUBLIC_CONTRACT_ID);
 
+// The contract stores bare CIDs; records are fetched through the gateway.
+export const ipfsUrl = (cid) => `https://gateway.pinata.cloud/ipfs/${cid}`;
+
 export const notifySuccess = (msg) => toast.success(msg, { duration: 2000 });
 export const notifyError = (msg) => toast.error(msg, { duration: 2000 });
\ No newline at end of file
@@ -4,7 +4,11 @@ import axios from "axios";
 import { setAllowed, isConnected } from "@stellar/freighter-api";
 
 import { retrievePublicKey, connectWallet, signTransaction } from "./wallet";
-import { numberToU64, stringToScValString } from "./value-converter";
+import {
+  numberToU32,
+  numberToU64,
+  stringToScValString,
+} from "./value-converter";
 import {
   BASE_FEE,
   TransactionBuilder,
@@ -13,7 +17,7 @@ import {
   Keypair,
 } from "@stellar/stellar-sdk";
 import { getUserInfo } from "@stellar/freighter-api";
-import { contract, server } from "./constants";
+import { contract, ipfsUrl, server } from "./constants";
 import { ownerPublicKey, notifyError, notifySuccess } from "./constants";
 
 export const VotingDappConext = React.createContext();
@@ -174,13 +178,15 @@ export const VotingDappProvider = ({ children }) => {
         },
       });
 
-      const url = `https://gateway.pinata.cloud/ipfs/${response.data.IpfsHash}`;
+      const cid = response.data.IpfsHash;
 
+      // Registration needs the registrant's signature, so the candidate is
+      // always the connected wallet that signs the transaction.
       const publicKey = await retrievePublicKey();
       const publicKeyAddr = new Address(publicKey);
-      await callContract("registerCandidate", [
+      await callContract("register_candidate", [
         stringToScValString(_name),
-        stringToScValString(url),
+        stringToScValString(cid),
         publicKeyAddr.toScVal(),
       ]);
 
@@ -261,14 +267,15 @@ export const VotingDappProvider = ({ children }) => {
         },
       });
 
-      const url = `https://gateway.pinata.cloud/ipfs/${response.data.IpfsHash}`;
+      const cid = response.data.IpfsHash;
 
+      // Registration needs the registrant's signature, so the voter is
+      // always the connected wallet that signs the transaction.
       const publicKey = await retrievePublicKey();
       const publicKeyAddr = new Address(publicKey).toScVal();
-      contract.call("registerVoter", _name, url);
       await callContract("registerVoter", [
         stringToScValString(_name),
-        stringToScValString(url),
+        stringToScValString(cid),
         publicKeyAddr,
       ]);
 
@@ -476,13 +483,13 @@ export const VotingDappProvider = ({ children }) => {
         },
       });
 
-      const url = `https://gateway.pinata.cloud/ipfs/${response.data.IpfsHash}`;
+      const cid = response.data.IpfsHash;
 
       const pk = await retrievePublicKey();
       const pka = new Address(pk);
       await callContract("update_voter", [
         stringToScValString(_name),
-        stringToScValString(url),
+        stringToScValString(cid),
         pka.toScVal(),
       ]);
 
@@ -570,13 +577,13 @@ export const VotingDappProvider = ({ children }) => {
         },
       });
 
-      const url = `https://gateway.pinata.cloud/ipfs/${response.data.IpfsHash}`;
+      const cid = response.data.IpfsHash;
 
       const pk = await retrievePublicKey();
       const pka = new Address(pk);
       await callContract("update_candidate", [
         stringToScValString(_name),
-        stringToScValString(url),
+        stringToScValString(cid),
         pka.toScVal(),
       ]);
 
@@ -655,7 +662,9 @@ export const VotingDappProvider = ({ children }) => {
   const initContractData = async () => {
     try {
       if (isWalletConnected) {
-        const [startDateN, endDateN] = await callContract("get_voting_time");
+        const [startDateN, endDateN] = scValToNative(
+          await callContract("get_voting_time")
+        ).map(Number);
 
         const timestamp1 = startDateN;
         const timestamp2 = endDateN;
@@ -675,8 +684,8 @@ export const VotingDappProvider = ({ children }) => {
         const item = {
           startDate: date1.toLocaleDateString("en-US", options),
           endDate: date2.toLocaleDateString("en-US", options),
-          startDateN: startDateN.toNumber(),
-          endDateN: endDateN.toNumber(),
+          startDateN,
+          endDateN,
         };
 
         return item;
@@ -696,10 +705,11 @@ export const VotingDappProvider = ({ children }) => {
             async ({
               ipfs,
               candidate_address,
-              registerId,
+              register_id,
               status,
-              voteCount,
+              vote_count,
               message,
+              profile,
             }) => {
               const {
                 data: {
@@ -721,15 +731,17 @@ export const VotingDappProvider = ({ children }) => {
                   image,
                   pdf,
                 },
-              } = await axios.get(ipfs, {});
+              } = await axios.get(ipfsUrl(ipfs), {});
 
               return {
                 address: candidate_address,
-                registerId: registerId?.toNumber(),
+                registerId: register_id,
                 status,
-                voteCount: voteCount?.toNumber(),
+                voteCount: Number(vote_count),
                 ipfs,
                 message,
+                affiliation: profile?.affiliation,
+                position: profile?.position,
                 _name,
                 _nominationForm,
                 _affidavit,
@@ -770,10 +782,10 @@ export const VotingDappProvider = ({ children }) => {
           scValToNative(voters).map(
             async ({
               ipfs,
-              voterAddress,
-              registerId,
+              voter_address,
+              register_id,
               status,
-              hasVoted,
+              has_voted,
               message,
             }) => {
               const {
@@ -793,13 +805,13 @@ export const VotingDappProvider = ({ children }) => {
                   image,
                   pdf,
                 },
-              } = await axios.get(ipfs, {});
+              } = await axios.get(ipfsUrl(ipfs), {});
 
               return {
-                address: voterAddress,
-                registerId: registerId?.toNumber(),
+                address: voter_address,
+                registerId: register_id,
                 status,
-                hasVoted,
+                hasVoted: has_voted,
                 message,
                 ipfs,
                 _name,
@@ -832,69 +844,25 @@ export const VotingDappProvider = ({ children }) => {
   const votedVoters = async () => {
     try {
       if (isWalletConnected) {
-        const voters = callContract("get_all_voters_who_voted");
-        console.log(voters);
-
-        const items = await Promise.all(
-          voters.map(
-            async ({
-              ipfs,
-              voterAddress,
-              registerId,
-              status,
-              hasVoted,
-              message,
-            }) => {
-              const {
-                data: {
-                  _name,
-                  _voterAddress,
-                  _photograph,
-                  _parentOrSpouseName,
-                  _gender,
-                  _dobOrAge,
-                  _addressDetails,
-                  _epicNumber,
-                  _partNumberAndName,
-                  _assemblyConstituencyNumberAndName,
-                  _issuingAuthoritySignature,
-                  _hologramAndBarcode,
-                  image,
-                  pdf,
-                },
-              } = await axios.get(ipfs, {});
-
-              return {
-                address: voterAddress,
-                registerId: registerId?.toNumber(),
-                status,
-                hasVoted,
-                message,
-                ipfs,
-                _name,
-                _voterAddress,
-                _photograph,
-                _parentOrSpouseName,
-                _gender,
-                _dobOrAge,
-                _addressDetails,
-                _epicNumber,
-                _partNumberAndName,
-                _assemblyConstituencyNumberAndName,
-                _issuingAuthoritySignature,
-                _hologramAndBarcode,
-                image,
-                pdf,
-              };
-            }
-          )
-        );
+        const pageSize = 100;
+        const items = [];
+        for (let start = 0; ; start += pageSize) {
+          const page = scValToNative(
+            await callContract("get_all_voters_who_voted", [
+              numberToU32(start),
+              numberToU32(pageSize),
+            ])
+          );
+          items.push(
+            ...page.map(({ voter, voted_at }) => ({
+              address: voter,
+              votedAt: Number(voted_at),
+            }))
+          );
+          if (page.length < pageSize) break;
+        }
 
-        items?.filter((user) =>
-          user.address.toLowerCase() === publicKey
-            ? setCheckVote(true)
-            : setCheckVote(false)
-        );
+        setCheckVote(items.some((user) => user.address === publicKey));
 
         return items;
       }
@@ -913,11 +881,15 @@ export const VotingDappProvider = ({ children }) => {
   const highestVotedCandidate = async () => {
     try {
       if (isWalletConnected) {
-        const candidate = await callContract("get_current_voting_status");
+        const candidates = scValToNative(
+          await callContract("get_current_voting_status")
+        );
 
-        console.log(candidate);
+        console.log(candidates);
 
-        if (candidates?.candidateAddress.toLowerCase() === zeroAddress) return;
+        // The contract returns a "NotFound" placeholder while no approved
+        // candidate has a vote.
+        if (candidates?.ipfs === "NotFound") return;
 
         const {
           data: {
@@ -939,13 +911,13 @@ export const VotingDappProvider = ({ children }) => {
             image,
             pdf,
           },
-        } = await axios.get(candidates?.ipfs);
+        } = await axios.get(ipfsUrl(candidates?.ipfs));
 
         const candidateData = {
-          address: candidates?.candidateAddress,
-          registerId: candidates?.registerId?.toNumber(),
+          address: candidates?.candidate_address,
+          registerId: candidates?.register_id,
           status: candidates?.status,
-          voteCount: candidates?.voteCount?.toNumber(),
+          voteCount: Number(candidates?.vote_count),
           ipfs: candidates?.ipfs,
           message: candidates?.message,
           _name,
@@ -1019,57 +991,65 @@ export const VotingDappProvider = ({ children }) => {
   const getWinner = async () => {
     try {
       if (isWalletConnected) {
-        const candidate = await callContract("get_winning_candidate");
+        const candidate = scValToNative(
+          await callContract("get_winning_candidate")
+        );
         console.log(candidate);
 
         const items = await Promise.all(
-          candidate.map(
+          [candidate].map(
             async ({
               ipfs,
-              voterAddress,
-              registerId,
+              candidate_address,
+              register_id,
               status,
-              hasVoted,
+              vote_count,
               message,
             }) => {
               const {
                 data: {
                   _name,
-                  _voterAddress,
-                  _photograph,
-                  _parentOrSpouseName,
-                  _gender,
-                  _dobOrAge,
-                  _addressDetails,
-                  _epicNumber,
-                  _partNumberAndName,
-                  _assemblyConstituencyNumberAndName,
-                  _issuingAuthoritySignature,
-                  _hologramAndBarcode,
+                  _nominationForm,
+                  _affidavit,
+                  _criminalAntecedents,
+                  _assetsAndLiabilities,
+                  _educationalQualifications,
+                  _electoralRollEntry,
+                  _securityDeposit,
+                  _partyAffiliation,
+                  _oathOrAffirmation,
+                  _photographs,
+                  _proofOfAge,
+                  _proofOfAddress,
+                  _panCardDetails,
+                  _voterIdCardDetails,
                   image,
                   pdf,
                 },
-              } = await axios.get(ipfs, {});
+              } = await axios.get(ipfsUrl(ipfs), {});
 
               return {
-                voterAddress,
-                registerId: registerId?.toNumber(),
+                address: candidate_address,
+                registerId: register_id,
                 status,
-                hasVoted,
+                voteCount: Number(vote_count),
                 message,
                 ipfs,
                 _name,
-                _voterAddress,
-                _photograph,
-                _parentOrSpouseName,
-                _gender,
-                _dobOrAge,
-                _addressDetails,
-                _epicNumber,
-                _partNumberAndName,
-                _assemblyConstituencyNumberAndName,
-                _issuingAuthoritySignature,
-                _hologramAndBarcode,
+                _nominationForm,
+                _affidavit,
+                _criminalAntecedents,
+                _assetsAndLiabilities,
+                _educationalQualifications,
+                _electoralRollEntry,
+                _securityDeposit,
+                _partyAffiliation,
+                _oathOrAffirmation,
+                _photographs,
+                _proofOfAge,
+                _proofOfAddress,
+                _panCardDetails,
+                _voterIdCardDetails,
                 image,
                 pdf,
               };
@@ -1089,7 +1069,9 @@ export const VotingDappProvider = ({ children }) => {
     try {
       if (!address) return notifyError("Kindly provide address");
       const pka = new Address(address);
-      const data = await callContract("get_voter", pka.toScVal());
+      const data = scValToNative(
+        await callContract("get_voter", pka.toScVal())
+      );
       const {
         data: {
           _name,
@@ -1107,14 +1089,14 @@ export const VotingDappProvider = ({ children }) => {
           image,
           pdf,
         },
-      } = await axios.get(data?.ipfs, {});
+      } = await axios.get(ipfsUrl(data?.ipfs), {});
 
       const voter = {
-        address: data?.voterAddress,
-        registerId: data?.registerId.toNumber(),
+        address: data?.voter_address,
+        registerId: data?.register_id,
         ipfs: data?.ipfs,
         status: data?.status,
-        hasVoted: data?.hasVoted,
+        hasVoted: data?.has_voted,
         message: data?.message,
         _name,
         _voterAddress,
@@ -1170,14 +1152,14 @@ export const VotingDappProvider = ({ children }) => {
           image,
           pdf,
         },
-      } = await axios.get(data?.ipfs, {});
+      } = await axios.get(ipfsUrl(data?.ipfs), {});
       console.log(_name);
       const candidate = {
-        address: data?.candidateAddress,
-        registerId: data?.registerId.toNumber(),
+        address: data?.candidate_address,
+        registerId: data?.register_id,
         ipfs: data?.ipfs,
         status: data?.status,
-        voteCount: data?.voteCount.toNumber(),
+        voteCount: Number(data?.vote_count),
         message: data?.message,
         _name,
         _nominationForm,
@@ -4,6 +4,10 @@ export const stringToScValString = (value) => {
   return nativeToScVal(value);
 };
 
+export const numberToU32 = (value) => {
+  return nativeToScVal(value, { type: "u32" });
+};
+
 export const numberToU64 = (value) => {
   return nativeToScVal(value, { type: "u64" });
 };
@@ -0,0 +1,46 @@
+//! Typed Rust client for the `VotingOrganization` contract.
+//!
+//! [`VotingOrganizationClient`] has one method per contract entrypoint, taking
+//! and returning the contract's own types, so callers never build `ScVal`s by
+//! hand. Every method has a `try_` variant that returns the contract
+//! [`Error`] instead of panicking when the call fails.
+//!
+//! ```
+//! use soroban_sdk::testutils::Address as _;
+//! use soroban_sdk::{Address, Env, String};
+//! use voting_organization_client::{Error, VotingOrganization, VotingOrganizationClient, APPROVED};
+//!
+//! let env = Env::default();
+//! env.mock_all_auths();
+//! let contract_id = env.register_contract(None, VotingOrganization);
+//! let client = VotingOrganizationClient::new(&env, &contract_id);
+//! client.init(&Address::generate(&env));
+//!
+//! let name = String::from_str(&env, "Ada");
+//! let cid = String::from_str(&env, "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+//! let voter = Address::generate(&env);
+//! client.registerVoter(&name, &cid, &voter);
+//! client.approve_voter(&voter, &String::from_str(&env, "Welcome"));
+//!
+//! let record = client.get_voter(&voter);
+//! assert_eq!(record.status, APPROVED);
+//! assert_eq!(record.register_id, 1);
+//!
+//! let again = client.try_registerVoter(&name, &cid, &voter);
+//! assert_eq!(again, Err(Ok(Error::AlreadyRegistered.into())));
+//! ```
+//!
+//! The client runs against a Soroban `Env`: a test environment, or a
+//! contract's own when calling across contracts. Contracts should not depend
+//! on this crate, though: linking it into a wasm build also exports every
+//! `VotingOrganization` entrypoint. They should generate the same client with
+//! `soroban_sdk::contractimport!` from the built `hello_world.wasm` instead.
+
+#![no_std]
+
+pub use hello_world::{
+    ArchivedElection, Ballot, Candidate, Error, Outcome, Participation, Profile, ProfileUpdate,
+    Question, Receipt, Results, Revision, Voter, VotingMethod, VotingOrganization,
+    VotingOrganizationClient, WithdrawalPolicy, APPROVED, PENDING, REJECTED, REVOKED, WITHDRAWN,
+};
+pub use soroban_sdk;
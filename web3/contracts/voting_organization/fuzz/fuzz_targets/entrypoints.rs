@@ -0,0 +1,370 @@
+//! Drives the contract through arbitrary sequences of registrar, voter and
+//! owner calls, with arbitrary names, CIDs and ledger timestamps, and checks
+//! after every call that:
+//!
+//! - no voter gets a second ballot counted unless revoting is allowed or
+//!   their ballot went to a candidate who withdrew under `ReenableVoters`,
+//! - no ballot is accepted outside the voting period,
+//! - the approved voter and candidate lists match the records' status,
+//! - the participation ledger lists each voter who voted exactly once.
+//!
+//! Calls are expected to fail often; only a broken invariant is a finding.
+
+#![no_main]
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use arbitrary::Arbitrary;
+use hello_world::{
+    VotingMethod, VotingOrganization, VotingOrganizationClient, WithdrawalPolicy, APPROVED,
+    WITHDRAWN,
+};
+use libfuzzer_sys::fuzz_target;
+use soroban_sdk::testutils::{Address as _, EnvTestConfig, Ledger};
+use soroban_sdk::{Address, Env, String, Vec};
+
+const CID: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+/// Addresses the calls pick from, so registrations and votes collide.
+const POOL: usize = 8;
+
+#[derive(Arbitrary, Debug)]
+enum Step {
+    SetTime(u64),
+    Advance(u16),
+    SetPeriod {
+        start: u64,
+        end: u64,
+    },
+    SetMethod(u8),
+    SetSeats(u32),
+    SetRevote(bool),
+    SetReenableVoters(bool),
+    RegisterVoter {
+        who: u8,
+        name: std::string::String,
+        // `None` registers with a valid CID, so most registrations land.
+        ipfs: Option<std::string::String>,
+    },
+    UpdateVoter {
+        who: u8,
+        name: std::string::String,
+        ipfs: Option<std::string::String>,
+    },
+    ReapplyVoter(u8),
+    ApproveVoter(u8),
+    RejectVoter(u8),
+    RevokeVoter(u8),
+    RegisterCandidate {
+        who: u8,
+        name: std::string::String,
+        ipfs: Option<std::string::String>,
+    },
+    ReapplyCandidate(u8),
+    ApproveCandidate(u8),
+    RejectCandidate(u8),
+    WithdrawCandidate(u8),
+    Vote {
+        voter: u8,
+        candidate: u8,
+    },
+    VoteForId {
+        voter: u8,
+        id: u32,
+    },
+    VoteApproval {
+        voter: u8,
+        choices: std::vec::Vec<u8>,
+    },
+    VoteRanked {
+        voter: u8,
+        choices: std::vec::Vec<u8>,
+    },
+    Abstain(u8),
+    Finalize,
+    Archive,
+}
+
+impl Step {
+    /// The voter casting a ballot, for the steps that cast one.
+    fn ballot_voter(&self) -> Option<u8> {
+        match *self {
+            Step::Vote { voter, .. }
+            | Step::VoteForId { voter, .. }
+            | Step::VoteApproval { voter, .. }
+            | Step::VoteRanked { voter, .. }
+            | Step::Abstain(voter) => Some(voter),
+            _ => None,
+        }
+    }
+}
+
+struct Harness<'a> {
+    env: Env,
+    client: VotingOrganizationClient<'a>,
+    owner: Address,
+    pool: std::vec::Vec<Address>,
+    /// The candidate each voter's counted single-choice ballot went to.
+    picks: RefCell<BTreeMap<Address, Address>>,
+}
+
+impl Harness<'_> {
+    fn new() -> Self {
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        env.mock_all_auths();
+        env.budget().reset_unlimited();
+        let id = env.register_contract(None, VotingOrganization);
+        let client = VotingOrganizationClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        client.init(&owner);
+        let pool = (0..POOL).map(|_| Address::generate(&env)).collect();
+        Harness {
+            env,
+            client,
+            owner,
+            pool,
+            picks: RefCell::default(),
+        }
+    }
+
+    fn str(&self, value: &str) -> String {
+        String::from_str(&self.env, value)
+    }
+
+    fn cid(&self, ipfs: &Option<std::string::String>) -> String {
+        self.str(ipfs.as_deref().unwrap_or(CID))
+    }
+
+    fn who(&self, index: u8) -> Address {
+        self.pool[usize::from(index) % POOL].clone()
+    }
+
+    fn choices(&self, indexes: &[u8]) -> Vec<Address> {
+        let mut choices = Vec::new(&self.env);
+        for &index in indexes {
+            choices.push_back(self.who(index));
+        }
+        choices
+    }
+
+    fn has_voted(&self, address: &Address) -> bool {
+        self.client.get_voter(address).has_voted
+    }
+
+    /// Whether the voter's counted ballot went to a candidate who has since
+    /// withdrawn, under a policy that lets them vote again.
+    fn voided(&self, voter: &Address) -> bool {
+        if self.client.get_withdrawal_policy() != WithdrawalPolicy::ReenableVoters {
+            return false;
+        }
+        match self.picks.borrow().get(voter) {
+            Some(candidate) => self.client.get_candidate(candidate).status == WITHDRAWN,
+            None => false,
+        }
+    }
+
+    /// Notes where an accepted step leaves the voters' ballots.
+    fn record(&self, step: &Step) {
+        let mut picks = self.picks.borrow_mut();
+        match step {
+            Step::Vote { voter, candidate } => {
+                picks.insert(self.who(*voter), self.who(*candidate));
+            }
+            Step::VoteForId { voter, id } => {
+                let candidate = self.client.get_candidate_by_id(id).candidate_address;
+                picks.insert(self.who(*voter), candidate);
+            }
+            Step::VoteApproval { voter, .. }
+            | Step::VoteRanked { voter, .. }
+            | Step::Abstain(voter) => {
+                picks.remove(&self.who(*voter));
+            }
+            Step::Archive => picks.clear(),
+            _ => {}
+        }
+    }
+
+    /// Runs one step, returning whether the call was accepted.
+    fn apply(&self, step: &Step) -> bool {
+        let c = &self.client;
+        let owner = &self.owner;
+        let reason = self.str("fuzz");
+        match step {
+            Step::SetTime(time) => {
+                self.env.ledger().set_timestamp(*time);
+                true
+            }
+            Step::Advance(by) => {
+                let now = self.env.ledger().timestamp();
+                self.env
+                    .ledger()
+                    .set_timestamp(now.saturating_add(u64::from(*by)));
+                true
+            }
+            Step::SetPeriod { start, end } => c.try_set_voting_period(start, end, owner).is_ok(),
+            Step::SetMethod(method) => {
+                let method = match method % 3 {
+                    0 => VotingMethod::Plurality,
+                    1 => VotingMethod::Approval,
+                    _ => VotingMethod::Ranked,
+                };
+                c.try_set_voting_method(&method, owner).is_ok()
+            }
+            Step::SetSeats(seats) => c.try_set_seats(seats, owner).is_ok(),
+            Step::SetRevote(allow) => c.try_set_allow_revote(allow, owner).is_ok(),
+            Step::SetReenableVoters(reenable) => {
+                let policy = if *reenable {
+                    WithdrawalPolicy::ReenableVoters
+                } else {
+                    WithdrawalPolicy::VoidVotes
+                };
+                c.try_set_withdrawal_policy(&policy, owner).is_ok()
+            }
+            Step::RegisterVoter { who, name, ipfs } => c
+                .try_registerVoter(&self.str(name), &self.cid(ipfs), &self.who(*who))
+                .is_ok(),
+            Step::UpdateVoter { who, name, ipfs } => c
+                .try_update_voter(&self.str(name), &self.cid(ipfs), &self.who(*who))
+                .is_ok(),
+            Step::ReapplyVoter(who) => c
+                .try_reapply_voter(&self.str("Voter"), &self.str(CID), &self.who(*who))
+                .is_ok(),
+            Step::ApproveVoter(who) => c.try_approve_voter(&self.who(*who), &reason).is_ok(),
+            Step::RejectVoter(who) => c.try_reject_voter(&self.who(*who), &reason).is_ok(),
+            Step::RevokeVoter(who) => c.try_revoke_voter(&self.who(*who), &reason).is_ok(),
+            Step::RegisterCandidate { who, name, ipfs } => c
+                .try_register_candidate(&self.str(name), &self.cid(ipfs), &self.who(*who))
+                .is_ok(),
+            Step::ReapplyCandidate(who) => c
+                .try_reapply_candidate(&self.str("Candidate"), &self.str(CID), &self.who(*who))
+                .is_ok(),
+            Step::ApproveCandidate(who) => {
+                c.try_approve_candidate(&self.who(*who), &reason).is_ok()
+            }
+            Step::RejectCandidate(who) => c.try_reject_candidate(&self.who(*who), &reason).is_ok(),
+            Step::WithdrawCandidate(who) => {
+                c.try_withdraw_candidate(&self.who(*who), &reason).is_ok()
+            }
+            Step::Vote { voter, candidate } => {
+                c.try_vote(&self.who(*candidate), &self.who(*voter)).is_ok()
+            }
+            Step::VoteForId { voter, id } => c.try_vote_for_id(id, &self.who(*voter)).is_ok(),
+            Step::VoteApproval { voter, choices } => c
+                .try_vote_approval(&self.choices(choices), &self.who(*voter))
+                .is_ok(),
+            Step::VoteRanked { voter, choices } => c
+                .try_vote_ranked(&self.choices(choices), &self.who(*voter))
+                .is_ok(),
+            Step::Abstain(voter) => c.try_abstain(&self.who(*voter)).is_ok(),
+            Step::Finalize => c.try_finalize_election(owner).is_ok(),
+            Step::Archive => c.try_archive_election(owner).is_ok(),
+        }
+    }
+
+    fn check_ballot(&self, step: &Step, voter: &Address, had_voted: bool, voided: bool) {
+        let now = self.env.ledger().timestamp();
+        let period = self.client.get_voting_time();
+        let (start, end) = (period.get_unchecked(0), period.get_unchecked(1));
+        assert!(
+            start <= now && now <= end,
+            "{step:?} accepted at {now}, outside the voting period {start}..={end}"
+        );
+        assert!(
+            !had_voted || voided || self.client.get_allow_revote(),
+            "{step:?} counted a second ballot for a voter who had already voted"
+        );
+        assert!(
+            self.has_voted(voter),
+            "{step:?} did not mark the voter as voted"
+        );
+    }
+
+    fn check_invariants(&self) {
+        let approved_voters = self.client.get_all_approved_voters();
+        let approved_candidates = self.client.get_all_approved_candidates();
+
+        for voter in approved_voters.iter() {
+            let record = self.client.get_voter(&voter.voter_address);
+            assert_eq!(record.status, APPROVED);
+        }
+        for candidate in approved_candidates.iter() {
+            let record = self.client.get_candidate(&candidate.candidate_address);
+            assert_eq!(record.status, APPROVED);
+        }
+
+        let listed_voters: std::vec::Vec<Address> =
+            approved_voters.iter().map(|v| v.voter_address).collect();
+        let listed_candidates: std::vec::Vec<Address> = approved_candidates
+            .iter()
+            .map(|c| c.candidate_address)
+            .collect();
+        assert_unique(&listed_voters, "approved voters");
+        assert_unique(&listed_candidates, "approved candidates");
+        for address in &self.pool {
+            let approved = self.client.get_voter(address).status == APPROVED;
+            assert_eq!(
+                approved,
+                listed_voters.contains(address),
+                "approved voter list disagrees with the record for {address:?}"
+            );
+            let approved = self.client.get_candidate(address).status == APPROVED;
+            assert_eq!(
+                approved,
+                listed_candidates.contains(address),
+                "approved candidate list disagrees with the record for {address:?}"
+            );
+        }
+
+        let turnout = self.client.get_voted_count();
+        let ledger = self.client.get_all_voters_who_voted(&0, &turnout);
+        assert_eq!(
+            ledger.len(),
+            turnout,
+            "participation ledger and turnout differ"
+        );
+        let participants: std::vec::Vec<Address> = ledger.iter().map(|p| p.voter).collect();
+        assert_unique(&participants, "participation ledger");
+        for address in &self.pool {
+            if self.has_voted(address) {
+                assert!(
+                    participants.contains(address),
+                    "{address:?} voted but is missing from the participation ledger"
+                );
+            }
+        }
+    }
+}
+
+fn assert_unique(addresses: &[Address], list: &str) {
+    for (i, address) in addresses.iter().enumerate() {
+        assert!(
+            !addresses[..i].contains(address),
+            "{address:?} appears twice in the {list}"
+        );
+    }
+}
+
+fuzz_target!(|steps: std::vec::Vec<Step>| {
+    let harness = Harness::new();
+    for step in &steps {
+        let ballot = step.ballot_voter().map(|voter| {
+            let voter = harness.who(voter);
+            let had_voted = harness.has_voted(&voter);
+            let voided = harness.voided(&voter);
+            (voter, had_voted, voided)
+        });
+
+        let accepted = harness.apply(step);
+
+        if accepted {
+            harness.record(step);
+        }
+        if let (true, Some((voter, had_voted, voided))) = (accepted, ballot) {
+            harness.check_ballot(step, &voter, had_voted, voided);
+        }
+        harness.check_invariants();
+    }
+});
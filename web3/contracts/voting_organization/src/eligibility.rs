@@ -0,0 +1,43 @@
+//! Proof formats accepted by the self-service registration entrypoints.
+//!
+//! Merkle roll: each leaf is `sha256(xdr(address))`, and each parent is the
+//! `sha256` of its two children concatenated smallest first, so a proof is
+//! just the list of sibling hashes from the leaf up to the root.
+//!
+//! Voucher: an ed25519 signature by the voucher signer over
+//! `xdr(contract) || election_id as 4 big-endian bytes || xdr(voter)`.
+
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+pub(crate) fn leaf(env: &Env, address: &Address) -> BytesN<32> {
+    env.crypto().sha256(&address.clone().to_xdr(env)).into()
+}
+
+pub(crate) fn verify_proof(
+    env: &Env,
+    root: &BytesN<32>,
+    leaf: BytesN<32>,
+    proof: &Vec<BytesN<32>>,
+) -> bool {
+    let mut node = leaf;
+    for sibling in proof.iter() {
+        let mut data = Bytes::new(env);
+        if node < sibling {
+            data.append(&node.into());
+            data.append(&sibling.into());
+        } else {
+            data.append(&sibling.into());
+            data.append(&node.into());
+        }
+        node = env.crypto().sha256(&data).into();
+    }
+
+    node == *root
+}
+
+pub(crate) fn voucher_message(env: &Env, election: u32, address: &Address) -> Bytes {
+    let mut message = env.current_contract_address().to_xdr(env);
+    message.extend_from_array(&election.to_be_bytes());
+    message.append(&address.clone().to_xdr(env));
+    message
+}
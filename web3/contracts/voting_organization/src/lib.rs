@@ -1,56 +1,355 @@
 #![no_std]
 
+mod eligibility;
+mod receipt;
+mod tally;
+mod validation;
+
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, vec, Address, Env, String, Symbol, Vec,
-    U256,
+    contract, contracterror, contractimpl, contracttype, panic_with_error, symbol_short, vec,
+    Address, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
 };
 
-const PENDING: Symbol = symbol_short!("Pending");
-const APPROVED: Symbol = symbol_short!("Approved");
-const REJECTED: Symbol = symbol_short!("Rejected");
+/// Values of `Voter::status` and `Candidate::status`. `Revoked` only
+/// applies to voters and `Withdrawn` only to candidates.
+pub const PENDING: Symbol = symbol_short!("Pending");
+pub const APPROVED: Symbol = symbol_short!("Approved");
+pub const REJECTED: Symbol = symbol_short!("Rejected");
+pub const REVOKED: Symbol = symbol_short!("Revoked");
+pub const WITHDRAWN: Symbol = symbol_short!("Withdrawn");
+
+const PENDING_MESSAGE: &str = "Currently your registration is pending";
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotFound = 1,
+    InvalidStatus = 2,
+    VotingStarted = 3,
+    VotingEnded = 4,
+    AlreadyRegistered = 5,
+    NotEligible = 6,
+    BatchTooLarge = 7,
+    InvalidProfile = 8,
+    InvalidName = 9,
+    InvalidCid = 10,
+    InvalidMessage = 11,
+    InvalidBallot = 12,
+    WrongVotingMethod = 13,
+    InvalidSeats = 14,
+    VotingNotEnded = 15,
+    InvalidQuestion = 16,
+    AlreadyAnswered = 17,
+    ElectionFinalized = 18,
+    Overflow = 19,
+    VotingInProgress = 20,
+    AlreadyMigrated = 21,
+    AlreadyInitialized = 22,
+}
 
+/// A voter's registration in the current election. Lookups of an unknown
+/// address return a placeholder with `ipfs` set to "NotFound".
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Voter {
-    voter_address: Address,
-    name: String,
-    ipfs: String,
-    register_id: U256,
-    status: Symbol,
-    has_voted: bool,
-    message: String,
+    pub voter_address: Address,
+    pub name: String,
+    /// CID of the registration document.
+    pub ipfs: String,
+    pub profile: Profile,
+    /// Position in registration order, from 1 in each election.
+    pub register_id: u32,
+    /// One of `PENDING`, `APPROVED`, `REJECTED` or `REVOKED`.
+    pub status: Symbol,
+    pub has_voted: bool,
+    /// The registrar's note on the latest decision.
+    pub message: String,
 }
 
+/// A candidate's registration in the current election. Lookups of an
+/// unknown address return a placeholder with `ipfs` set to "NotFound".
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Candidate {
-    candidate_address: Address,
-    name: String,
-    ipfs: String,
-    register_id: U256,
-    status: Symbol,
-    vote_count: U256,
-    message: String,
+    pub candidate_address: Address,
+    pub name: String,
+    /// CID of the registration document.
+    pub ipfs: String,
+    pub profile: Profile,
+    /// Position in registration order, from 1 in each election, numbered
+    /// separately from voters.
+    pub register_id: u32,
+    /// One of `PENDING`, `APPROVED`, `REJECTED` or `WITHDRAWN`.
+    pub status: Symbol,
+    /// Ballots counting for the candidate; approvals under `Approval`, and
+    /// first preferences under `Ranked`.
+    pub vote_count: u64,
+    /// The registrar's note on the latest decision.
+    pub message: String,
+}
+
+/// Structured details shown next to a voter or candidate without fetching
+/// their ipfs document. Every field is optional; an empty string means
+/// unset. `manifesto` and `image` are IPFS CIDs, and `metadata` holds up to
+/// `MAX_METADATA_ENTRIES` free-form pairs.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Profile {
+    pub affiliation: String,
+    pub position: String,
+    pub manifesto: String,
+    pub image: String,
+    pub contact: String,
+    pub metadata: Map<String, String>,
+}
+
+impl Profile {
+    fn empty(env: &Env) -> Self {
+        Profile {
+            affiliation: String::from_str(env, ""),
+            position: String::from_str(env, ""),
+            manifesto: String::from_str(env, ""),
+            image: String::from_str(env, ""),
+            contact: String::from_str(env, ""),
+            metadata: Map::new(env),
+        }
+    }
+}
+
+/// Summary of an election that has been moved out of the live slot by
+/// `archive_election`. The rolls and results stay readable through the
+/// `get_archived_*` entrypoints under the same `election_id`.
+/// `ballots_cast` is the length of the receipt chain, answer sets included.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchivedElection {
+    pub election_id: u32,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub archived_at: u64,
+    pub registered_voters: u32,
+    pub registered_candidates: u32,
+    pub approved_voters: u32,
+    pub approved_candidates: u32,
+    pub ballots_cast: u32,
+    pub ballot_root: BytesN<32>,
+}
+
+/// What a voter's ballot went to. `Candidate` is a single choice cast with
+/// `vote` under any method; under `Ranked` it counts as a ranking of one.
+/// `Abstain` records that the voter took part without choosing anyone.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Ballot {
+    Candidate(Address),
+    Approval(Vec<Address>),
+    Ranked(Vec<Address>),
+    Abstain,
+}
+
+/// Proof that a ballot or a set of referendum answers was recorded: its
+/// position in the election's ballot sequence and the chained hash at that
+/// position (see `receipt.rs`). A revote gets a new receipt; the earlier one
+/// stays verifiable but no longer reflects the counted ballot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Receipt {
+    pub election_id: u32,
+    pub sequence: u32,
+    pub hash: BytesN<32>,
+}
+
+/// Standing of an election for quorum checks. `turnout` counts every voter
+/// who cast a ballot or answered a question, abstentions included;
+/// `eligible` is the number of currently approved voters.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Results {
+    pub candidates: Vec<Candidate>,
+    pub abstentions: u32,
+    pub turnout: u32,
+    pub eligible: u32,
+}
+
+/// How ballots are cast and counted for an election's seats.
+///
+/// - `Plurality`: one choice per voter; the seats go to the candidates with
+///   the most votes.
+/// - `Approval`: voters approve any number of candidates with
+///   `vote_approval`; the seats go to the most approved.
+/// - `Ranked`: voters rank candidates with `vote_ranked`; seats are filled
+///   by Single Transferable Vote.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VotingMethod {
+    Plurality,
+    Approval,
+    Ranked,
+}
+
+/// What happens to ballots already cast for a candidate who withdraws
+/// during voting. With `VoidVotes` the ballots no longer count and the
+/// voters have used their vote; with `ReenableVoters` those voters may vote
+/// again for a remaining candidate. This only applies to single-choice
+/// ballots: approval and ranked ballots keep counting for the voter's other
+/// choices.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WithdrawalPolicy {
+    VoidVotes,
+    ReenableVoters,
+}
+
+/// Result of one item in a batch decision. `Failed` carries the `Error`
+/// code that the single-item entrypoint would have failed with.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Applied,
+    Failed(u32),
+}
+
+/// A superseded name, ipfs and profile, kept so disputes about what a
+/// record said at a given time can be settled. `changed_at` is when it was
+/// replaced.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Revision {
+    pub name: String,
+    pub ipfs: String,
+    pub profile: Profile,
+    pub changed_at: u64,
+    pub changed_by: Address,
+}
+
+/// A profile change an approved candidate submitted, waiting for a
+/// registrar to approve it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileUpdate {
+    pub name: String,
+    pub ipfs: String,
+    pub profile: Profile,
+    pub submitted_at: u64,
 }
 
 #[contracttype]
 pub enum Voters {
-    Voter(Address),
+    Voter(u32, Address),
+    VoterHistory(u32, Address),
+    VoterById(u32, u32),
 }
 
 #[contracttype]
 pub enum Candidates {
-    Candidate(Address),
+    Candidate(u32, Address),
+    CandidateHistory(u32, Address),
+    PendingUpdate(u32, Address),
+    CandidateById(u32, u32),
+}
+
+/// A referendum question put to the approved voters alongside the
+/// candidate election, answered by picking one of `options` (for example
+/// "Yes", "No", "Abstain").
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Question {
+    pub text: String,
+    pub options: Vec<String>,
+}
+
+/// An entry in the participation ledger: who voted or answered and when
+/// they first did, but not for whom or what.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Participation {
+    pub voter: Address,
+    pub voted_at: u64,
+}
+
+#[contracttype]
+pub enum Ballots {
+    Ballot(u32, Address),
+    Participant(u32, u32),
+    Participated(u32, Address),
+    Receipt(u32, Address),
+    Chain(u32, u32),
+}
+
+#[contracttype]
+pub enum Questions {
+    Tally(u32, u32),
+    Answer(u32, u32, Address),
+}
+
+#[contracttype]
+pub enum Eligibility {
+    Allowed(u32, Address),
+}
+
+#[contracttype]
+pub enum Elections {
+    Archived(u32),
+    Finalized(u32),
 }
 
 const OWNER: Symbol = symbol_short!("Owner");
+const ELECTION_ID: Symbol = symbol_short!("ElectId");
+const VOUCHER_SIGNER: Symbol = symbol_short!("VchSigner");
+const VERSION: Symbol = symbol_short!("Version");
+
+// Storage layout version written by this code. `init` stores it, so every
+// deployment that can be upgraded has one.
+const CONTRACT_VERSION: u32 = 2;
+
+// Everything below is stored per election as `(KEY, election_id)`, so that
+// archiving only has to move the `ELECTION_ID` pointer forward.
 const REGISTERED_VOTERS: Symbol = symbol_short!("RegVot");
 const REGISTERED_CANDIDATES: Symbol = symbol_short!("RegCan");
 const APPROVED_VOTERS: Symbol = symbol_short!("ApproVot");
 const APPROVED_CANDIDATES: Symbol = symbol_short!("ApproCan");
-const VOTED_VOTERS: Symbol = symbol_short!("VotVoted");
 const VOTER_ID_COUNTER: Symbol = symbol_short!("votIdCntr");
 const CANDIDATE_ID_COUNTER: Symbol = symbol_short!("canIdCntr");
 const START_TIME: Symbol = symbol_short!("StartTime");
 const END_TIME: Symbol = symbol_short!("EndTime");
+const WITHDRAWAL_POLICY: Symbol = symbol_short!("WdPolicy");
+const ELIGIBILITY_ROOT: Symbol = symbol_short!("EligRoot");
+const SEATS: Symbol = symbol_short!("Seats");
+const VOTING_METHOD: Symbol = symbol_short!("VoteMthd");
+const QUESTIONS: Symbol = symbol_short!("Questions");
+const ABSTENTIONS: Symbol = symbol_short!("Abstained");
+const TURNOUT: Symbol = symbol_short!("Turnout");
+const REVOTE: Symbol = symbol_short!("Revote");
+const LAST_RECEIPT: Symbol = symbol_short!("LastRcpt");
+
+// Soroban's per-transaction limit on ledger entries written. The test
+// environment does not enforce it, so batch sizes are derived from it.
+const TX_MAX_WRITE_ENTRIES: u32 = 25;
+
+// Items in a batch decision. Each item writes its record, and the batch also
+// writes the approved list and the owner's auth nonce. Reads are the written
+// entries plus the contract instance and code, the owner and the election
+// id, well inside the 40-entry read limit.
+const MAX_BATCH_SIZE: u32 = TX_MAX_WRITE_ENTRIES - 2;
+
+// Members in an `import_voters` batch. Each member writes its record and its
+// id lookup, and the batch also writes the id counter, the registered and
+// approved lists and the owner's auth nonce.
+const MAX_IMPORT_SIZE: u32 = (TX_MAX_WRITE_ENTRIES - 4) / 2;
+
+// Questions per election, keeping a ballot answering all of them inside the
+// per-transaction write limit.
+const MAX_QUESTIONS: u32 = 10;
+
+// Largest page returned by paginated getters.
+const MAX_PAGE_SIZE: u32 = 100;
+
+// Revisions kept per voter or candidate record.
+const MAX_REVISIONS: u32 = 10;
+
+// Roughly one day of ledgers at 5s close time.
+const DAY_IN_LEDGERS: u32 = 17280;
 
 #[contract]
 pub struct VotingOrganization;
@@ -58,509 +357,1958 @@ pub struct VotingOrganization;
 #[contractimpl]
 impl VotingOrganization {
     fn owner_only(env: &Env, address: Address) {
-        let addr: Address = Address::from_string(&String::from_str(env, ""));
-        let stored_addr = env.storage().persistent().get(&OWNER).unwrap_or(addr);
+        let stored_addr: Option<Address> = env.storage().persistent().get(&OWNER);
 
-        if stored_addr != address {
+        if stored_addr != Some(address.clone()) {
             panic!("can only be called by owner");
         }
+        address.require_auth();
     }
 
-    fn only_during_voting_period(env: &Env) {
-        let start_time = env.storage().persistent().get(&START_TIME).unwrap_or(0);
-        let end_time = env.storage().persistent().get(&END_TIME).unwrap_or(0);
+    fn owner_auth(env: &Env) {
+        let owner: Address = env
+            .storage()
+            .persistent()
+            .get(&OWNER)
+            .expect("contract is not initialized");
+        owner.require_auth();
+    }
 
-        if !(env.ledger().timestamp() >= start_time && env.ledger().timestamp() <= end_time) {
+    fn only_during_voting_period(env: &Env) {
+        if !Self::voting_open(env, Self::current_election(env)) {
             panic!("Voting is not active")
         }
     }
 
-    pub fn init(env: Env, owner_address: Address) {
-        env.storage().persistent().set(&OWNER, &owner_address);
-        env.storage().persistent().set(&VOTER_ID_COUNTER, &1);
-        env.storage().persistent().set(&CANDIDATE_ID_COUNTER, &1);
-    }
-
-    pub fn registerVoter(env: Env, name: String, ipfs: String, address: Address) {
-        const PENDING_MESSAGE: &str = "Currently your registration is pending";
-        let voter_id_key = Voters::Voter(address.clone());
-        let id_counter_key = VOTER_ID_COUNTER;
-
-        let id_counter = env
+    fn voting_open(env: &Env, election: u32) -> bool {
+        let start_time = env
             .storage()
             .persistent()
-            .get(&id_counter_key)
-            .unwrap_or_default();
-
-        let new_voter = Voter {
-            voter_address: address.clone(),
-            name,
-            ipfs,
-            has_voted: false,
-            message: String::from_str(&env, PENDING_MESSAGE),
-            register_id: U256::from_u32(&env, id_counter),
-            status: PENDING,
-        };
-
-        env.storage().persistent().set(&voter_id_key, &new_voter);
-        let mut registered_voters: Vec<Address> = env
+            .get(&(START_TIME, election))
+            .unwrap_or(0);
+        let end_time = env
             .storage()
             .persistent()
-            .get(&REGISTERED_VOTERS)
-            .unwrap_or(vec![&env]);
+            .get(&(END_TIME, election))
+            .unwrap_or(0);
 
-        registered_voters.push_back(address);
+        env.ledger().timestamp() >= start_time && env.ledger().timestamp() <= end_time
+    }
 
+    fn version(env: &Env) -> u32 {
         env.storage()
             .persistent()
-            .set(&REGISTERED_VOTERS, &registered_voters);
+            .get(&VERSION)
+            .unwrap_or(CONTRACT_VERSION)
+    }
 
-        env.storage()
-            .persistent()
-            .set(&id_counter_key, &(id_counter + 1));
+    fn current_election(env: &Env) -> u32 {
+        env.storage().persistent().get(&ELECTION_ID).unwrap_or(1)
     }
 
-    pub fn register_candidate(env: Env, name: String, ipfs: String, address: Address) {
-        const PENDING_MESSAGE: &str = "Currently your registration is pending";
-        let candidate_id_key = Candidates::Candidate(address.clone());
-        let id_counter_key = VOTER_ID_COUNTER;
+    fn archived_only(env: &Env, election: u32) {
+        assert!(
+            election != 0 && election < Self::current_election(env),
+            "Election is not archived"
+        );
+    }
+
+    fn voters_of(env: &Env, election: u32, list: Symbol) -> Vec<Voter> {
+        let mut voters: Vec<Voter> = vec![env];
 
-        let id_counter = env
+        let addresses: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&id_counter_key)
-            .unwrap_or_default();
+            .get(&(list, election))
+            .unwrap_or(vec![env]);
 
-        let new_candidate = Candidate {
-            candidate_address: address.clone(),
-            name,
-            ipfs,
-            message: String::from_str(&env, PENDING_MESSAGE),
-            register_id: U256::from_u32(&env, id_counter),
-            status: PENDING,
-            vote_count: U256::from_u32(&env, 0),
-        };
+        for v in addresses.iter() {
+            let key = Voters::Voter(election, v.clone());
+            let voter = env.storage().persistent().get(&key).unwrap_or(Voter {
+                has_voted: false,
+                ipfs: String::from_str(env, "NotFound"),
+                profile: Profile::empty(env),
+                message: String::from_str(env, ""),
+                name: String::from_str(env, ""),
+                register_id: 0,
+                status: REJECTED,
+                voter_address: v,
+            });
 
-        env.storage()
-            .persistent()
-            .set(&candidate_id_key, &new_candidate);
+            if voter.ipfs != String::from_str(env, "NotFound") {
+                voters.push_back(voter);
+            }
+        }
 
-        let mut registered_voters: Vec<Address> = env
+        voters
+    }
+
+    fn candidates_of(env: &Env, election: u32, list: Symbol) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = vec![env];
+
+        let addresses: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&REGISTERED_CANDIDATES)
-            .unwrap_or(vec![&env]);
+            .get(&(list, election))
+            .unwrap_or(vec![env]);
 
-        registered_voters.push_back(address);
+        for c in addresses.iter() {
+            let key = Candidates::Candidate(election, c.clone());
+            let candidate = env.storage().persistent().get(&key).unwrap_or(Candidate {
+                ipfs: String::from_str(env, "NotFound"),
+                profile: Profile::empty(env),
+                message: String::from_str(env, ""),
+                name: String::from_str(env, ""),
+                register_id: 0,
+                status: REJECTED,
+                candidate_address: c,
+                vote_count: 0,
+            });
+
+            if candidate.ipfs != String::from_str(env, "NotFound") {
+                candidates.push_back(candidate);
+            }
+        }
+
+        candidates
+    }
+
+    fn voter_of(env: &Env, election: u32, addr: Address) -> Voter {
+        let key = Voters::Voter(election, addr.clone());
+        env.storage().persistent().get(&key).unwrap_or(Voter {
+            ipfs: String::from_str(env, "NotFound"),
+            profile: Profile::empty(env),
+            message: String::from_str(env, ""),
+            name: String::from_str(env, ""),
+            register_id: 0,
+            status: REJECTED,
+            voter_address: addr,
+            has_voted: false,
+        })
+    }
+
+    fn candidate_of(env: &Env, election: u32, addr: Address) -> Candidate {
+        let key = Candidates::Candidate(election, addr.clone());
+        env.storage().persistent().get(&key).unwrap_or(Candidate {
+            ipfs: String::from_str(env, "NotFound"),
+            profile: Profile::empty(env),
+            message: String::from_str(env, ""),
+            name: String::from_str(env, ""),
+            register_id: 0,
+            status: REJECTED,
+            candidate_address: addr,
+            vote_count: 0,
+        })
+    }
 
+    fn voter_address(env: &Env, election: u32, id: u32) -> Address {
         env.storage()
             .persistent()
-            .set(&REGISTERED_CANDIDATES, &registered_voters);
+            .get(&Voters::VoterById(election, id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotFound))
+    }
 
+    fn candidate_address(env: &Env, election: u32, id: u32) -> Address {
         env.storage()
             .persistent()
-            .set(&id_counter_key, &(id_counter + 1));
+            .get(&Candidates::CandidateById(election, id))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotFound))
     }
 
-    pub fn approve_voter(env: Env, address: Address, message: String) {
-        Self::owner_only(&env, address.clone());
-
-        let key = Voters::Voter(address.clone());
-        let mut voter = env.storage().persistent().get(&key).unwrap_or(Voter {
-            name: String::from_str(&env, ""),
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            has_voted: false,
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            voter_address: address.clone(),
-        });
+    fn existing_voter(env: &Env, election: u32, addr: Address) -> Voter {
+        env.storage()
+            .persistent()
+            .get(&Voters::Voter(election, addr))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotFound))
+    }
 
-        assert_ne!(
-            voter.ipfs,
-            String::from_str(&env, "NotFound"),
-            "Voter not found"
-        );
+    fn existing_candidate(env: &Env, election: u32, addr: Address) -> Candidate {
+        env.storage()
+            .persistent()
+            .get(&Candidates::Candidate(election, addr))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotFound))
+    }
 
-        voter.status = APPROVED;
-        voter.message = message;
+    fn require_status(env: &Env, status: &Symbol, expected: Symbol) {
+        if *status != expected {
+            panic_with_error!(env, Error::InvalidStatus);
+        }
+    }
 
-        env.storage().persistent().set(&key, &voter);
+    fn remove_from_list(env: &Env, election: u32, list: Symbol, addr: &Address) {
+        let mut addresses: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(list.clone(), election))
+            .unwrap_or(vec![env]);
+
+        if let Some(i) = addresses.first_index_of(addr) {
+            addresses.remove(i);
+            env.storage()
+                .persistent()
+                .set(&(list, election), &addresses);
+        }
+    }
 
-        let mut approved_voters: Vec<Address> = env
+    /// Fails once the election's start time has passed or any ballot has
+    /// been cast, so moving the start time back out cannot reopen settings.
+    fn before_voting(env: &Env, election: u32) {
+        let start_time: u64 = env
             .storage()
             .persistent()
-            .get(&APPROVED_VOTERS)
-            .unwrap_or(vec![&env]);
+            .get(&(START_TIME, election))
+            .unwrap_or(0);
+        if (start_time != 0 && env.ledger().timestamp() >= start_time)
+            || Self::last_receipt(env, election).is_some()
+        {
+            panic_with_error!(env, Error::VotingStarted);
+        }
+    }
 
-        approved_voters.push_back(address);
+    fn voting_method(env: &Env, election: u32) -> VotingMethod {
         env.storage()
             .persistent()
-            .set(&APPROVED_VOTERS, &approved_voters);
+            .get(&(VOTING_METHOD, election))
+            .unwrap_or(VotingMethod::Plurality)
     }
 
-    pub fn approve_candidate(env: Env, address: Address, message: String) {
-        Self::owner_only(&env, address.clone());
+    fn seats(env: &Env, election: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(SEATS, election))
+            .unwrap_or(1)
+    }
 
-        let key = Candidates::Candidate(address.clone());
-        let mut candidate = env.storage().persistent().get(&key).unwrap_or(Candidate {
-            name: String::from_str(&env, ""),
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            candidate_address: address.clone(),
-            vote_count: U256::from_u32(&env, 0),
-        });
+    /// Checks `voter_address` may cast a ballot now and returns their
+    /// record marked as having voted. The caller saves it. Counts the voter
+    /// towards turnout the first time they vote; when a repeat voter may
+    /// vote again, because revoting is allowed or their ballot was voided by
+    /// a withdrawal, their previous ballot is retracted instead.
+    fn ballot_voter(env: &Env, election: u32, voter_address: Address) -> Voter {
+        voter_address.require_auth();
+        let mut voter = Self::voter_of(env, election, voter_address.clone());
 
         assert_ne!(
-            candidate.ipfs,
-            String::from_str(&env, "NotFound"),
-            "Voter not found"
+            voter.ipfs,
+            String::from_str(env, "NotFound"),
+            "Account Not Found"
         );
+        assert_eq!(voter.status, APPROVED, "You are not an approved voter.");
+        let revoting = voter.has_voted
+            && (Self::revote_allowed(env, election)
+                || Self::ballot_voided(env, election, voter_address.clone()));
+        assert!(!voter.has_voted || revoting, "You have already voted.");
+
+        if revoting {
+            Self::retract_ballot(env, election, voter_address);
+        } else if !voter.has_voted {
+            Self::record_participation(env, election, voter_address);
+        }
+        voter.has_voted = true;
+        voter
+    }
 
-        candidate.status = APPROVED;
-        candidate.message = message;
-
-        env.storage().persistent().set(&key, &candidate);
+    /// Appends the voter to the participation ledger, whose length is the
+    /// election's turnout, the first time they cast a ballot or answer a
+    /// question.
+    fn record_participation(env: &Env, election: u32, voter: Address) {
+        let key = Ballots::Participated(election, voter.clone());
+        if env.storage().persistent().has(&key) {
+            return;
+        }
+        env.storage().persistent().set(&key, &true);
 
-        let mut approved_candidate: Vec<Address> = env
-            .storage()
+        let index = Self::turnout(env, election);
+        let entry = Participation {
+            voter,
+            voted_at: env.ledger().timestamp(),
+        };
+        env.storage()
             .persistent()
-            .get(&APPROVED_CANDIDATES)
-            .unwrap_or_else(|| vec![&env]);
+            .set(&Ballots::Participant(election, index), &entry);
+        Self::increment(env, &(TURNOUT, election));
+    }
 
-        approved_candidate.push_back(address);
+    fn turnout(env: &Env, election: u32) -> u32 {
         env.storage()
             .persistent()
-            .set(&APPROVED_CANDIDATES, &approved_candidate);
+            .get(&(TURNOUT, election))
+            .unwrap_or(0)
     }
 
-    pub fn reject_voter(env: Env, address: Address, message: String) {
-        Self::owner_only(&env, address.clone());
-
-        let key = Voters::Voter(address.clone());
-        let mut voter = env.storage().persistent().get(&key).unwrap_or(Voter {
-            name: String::from_str(&env, ""),
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            has_voted: false,
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            voter_address: address.clone(),
-        });
+    fn participation(env: &Env, election: u32, start: u32, limit: u32) -> Vec<Participation> {
+        let end = Self::turnout(env, election).min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+        let mut page = vec![env];
+        for i in start..end {
+            page.push_back(
+                env.storage()
+                    .persistent()
+                    .get::<_, Participation>(&Ballots::Participant(election, i))
+                    .unwrap(),
+            );
+        }
+        page
+    }
 
-        assert_ne!(
-            voter.ipfs,
-            String::from_str(&env, "NotFound"),
-            "Voter not found"
-        );
+    /// Takes the next id from an election's voter or candidate counter.
+    /// Ids start at 1.
+    fn next_id(env: &Env, key: &(Symbol, u32)) -> Result<u32, Error> {
+        let id: u32 = env.storage().persistent().get(key).unwrap_or(1);
+        let next = id.checked_add(1).ok_or(Error::Overflow)?;
+        env.storage().persistent().set(key, &next);
+        Ok(id)
+    }
 
-        voter.status = APPROVED;
-        voter.message = message;
+    fn increment<K>(env: &Env, key: &K)
+    where
+        K: IntoVal<Env, Val>,
+    {
+        let count: u32 = env.storage().persistent().get(key).unwrap_or(0);
+        let count = Self::ensure(env, count.checked_add(1).ok_or(Error::Overflow));
+        env.storage().persistent().set(key, &count);
+    }
 
-        env.storage().persistent().set(&key, &voter);
+    fn decrement<K>(env: &Env, key: &K)
+    where
+        K: IntoVal<Env, Val>,
+    {
+        let count: u32 = env.storage().persistent().get(key).unwrap_or(0);
+        let count = Self::ensure(env, count.checked_sub(1).ok_or(Error::Overflow));
+        env.storage().persistent().set(key, &count);
     }
 
-    pub fn reject_candidate(env: Env, address: Address, message: String) {
-        Self::owner_only(&env, address.clone());
+    /// Saves the voter's record and ballot and appends the ballot to the
+    /// election's receipt chain.
+    fn cast(env: &Env, election: u32, voter: Voter, ballot: Ballot) -> Receipt {
+        let voter_address = voter.voter_address.clone();
+        env.storage()
+            .persistent()
+            .set(&Voters::Voter(election, voter_address.clone()), &voter);
 
-        let key = Candidates::Candidate(address.clone());
-        let mut candidate = env.storage().persistent().get(&key).unwrap_or(Voter {
-            name: String::from_str(&env, ""),
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            has_voted: false,
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            voter_address: address.clone(),
+        let receipt = Self::append(env, election, |previous, sequence| {
+            receipt::link(env, previous, sequence, &voter_address, &ballot)
         });
 
-        assert_ne!(
-            candidate.ipfs,
-            String::from_str(&env, "NotFound"),
-            "Voter not found"
+        let storage = env.storage().persistent();
+        storage.set(&Ballots::Ballot(election, voter_address.clone()), &ballot);
+        storage.set(&Ballots::Receipt(election, voter_address.clone()), &receipt);
+
+        env.events().publish(
+            (symbol_short!("ballot"), election),
+            (
+                receipt.sequence,
+                voter_address,
+                ballot,
+                receipt.hash.clone(),
+            ),
         );
 
-        candidate.status = APPROVED;
-        candidate.message = message;
-
-        env.storage().persistent().set(&key, &candidate);
+        receipt
     }
 
-    pub fn set_voting_period(env: Env, start_time: u64, end_time: u64, address: Address) {
-        Self::owner_only(&env, address.clone());
-
-        assert!(start_time < end_time, "Start time must be before end time.");
+    /// Extends the election's receipt chain by one entry, whose hash `link`
+    /// computes from the previous hash and the new sequence number.
+    fn append(
+        env: &Env,
+        election: u32,
+        link: impl FnOnce(&BytesN<32>, u32) -> BytesN<32>,
+    ) -> Receipt {
+        let (sequence, previous) = match Self::last_receipt(env, election) {
+            Some(last) => (
+                Self::ensure(env, last.sequence.checked_add(1).ok_or(Error::Overflow)),
+                last.hash,
+            ),
+            None => (1, receipt::genesis(env)),
+        };
+        let receipt = Receipt {
+            election_id: election,
+            sequence,
+            hash: link(&previous, sequence),
+        };
 
-        env.storage().persistent().set(&START_TIME, &start_time);
-        env.storage().persistent().set(&END_TIME, &end_time);
+        let storage = env.storage().persistent();
+        storage.set(&Ballots::Chain(election, sequence), &receipt.hash);
+        storage.set(&(LAST_RECEIPT, election), &receipt);
+        receipt
     }
 
-    pub fn get_all_registered_voters(env: Env) -> Vec<Voter> {
-        let mut voters: Vec<Voter> = vec![&env];
+    /// Head of the election's receipt chain and the number of ballots in
+    /// it; the genesis hash before any ballot is cast.
+    fn ballot_root(env: &Env, election: u32) -> (BytesN<32>, u32) {
+        match Self::last_receipt(env, election) {
+            Some(last) => (last.hash, last.sequence),
+            None => (receipt::genesis(env), 0),
+        }
+    }
 
-        let registered_voters: Vec<Address> = env
-            .storage()
+    fn is_finalized(env: &Env, election: u32) -> bool {
+        env.storage()
             .persistent()
-            .get(&REGISTERED_VOTERS)
-            .unwrap_or(vec![&env]);
-
-        for v in registered_voters.iter() {
-            let key = Voters::Voter(v.clone());
-            let voter = env.storage().persistent().get(&key).unwrap_or(Voter {
-                has_voted: false,
-                ipfs: String::from_str(&env, "NotFound"),
-                message: String::from_str(&env, ""),
-                name: String::from_str(&env, ""),
-                register_id: U256::from_u32(&env, 0),
-                status: REJECTED,
-                voter_address: v,
-            });
+            .has(&Elections::Finalized(election))
+    }
 
-            if voter.ipfs != String::from_str(&env, "NotFound") {
-                voters.push_back(voter);
-            }
-        }
+    /// Freezes the ballot root of `election`, which must not already be
+    /// frozen.
+    fn freeze(env: &Env, election: u32) -> (BytesN<32>, u32) {
+        let (root, ballots) = Self::ballot_root(env, election);
+        env.storage()
+            .persistent()
+            .set(&Elections::Finalized(election), &root);
+        env.events().publish(
+            (symbol_short!("election"), symbol_short!("finalized")),
+            (election, root.clone(), ballots),
+        );
+        (root, ballots)
+    }
 
-        return voters;
+    fn last_receipt(env: &Env, election: u32) -> Option<Receipt> {
+        env.storage().persistent().get(&(LAST_RECEIPT, election))
     }
 
-    pub fn get_all_registered_candidates(env: Env) -> Vec<Candidate> {
-        let mut candidates: Vec<Candidate> = vec![&env];
+    fn revote_allowed(env: &Env, election: u32) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(REVOTE, election))
+            .unwrap_or(false)
+    }
 
-        let registered_candidates: Vec<Address> = env
+    /// Takes a voter's current ballot back out of the tallies so a new one
+    /// can replace it.
+    fn retract_ballot(env: &Env, election: u32, voter_address: Address) {
+        match env
             .storage()
             .persistent()
-            .get(&REGISTERED_CANDIDATES)
-            .unwrap_or(vec![&env]);
+            .get(&Ballots::Ballot(election, voter_address))
+        {
+            Some(Ballot::Candidate(c)) => Self::remove_vote(env, election, c),
+            Some(Ballot::Approval(candidates)) => {
+                for c in candidates {
+                    Self::remove_vote(env, election, c);
+                }
+            }
+            Some(Ballot::Ranked(ranking)) => {
+                Self::remove_vote(env, election, ranking.get_unchecked(0))
+            }
+            Some(Ballot::Abstain) => Self::decrement(env, &(ABSTENTIONS, election)),
+            None => {}
+        }
+    }
 
-        for c in registered_candidates.iter() {
-            let key = Candidates::Candidate(c.clone());
-            let candidate = env.storage().persistent().get(&key).unwrap_or(Candidate {
-                ipfs: String::from_str(&env, "NotFound"),
-                message: String::from_str(&env, ""),
-                name: String::from_str(&env, ""),
-                register_id: U256::from_u32(&env, 0),
-                status: REJECTED,
-                candidate_address: c,
-                vote_count: U256::from_u32(&env, 0),
-            });
+    fn results(env: &Env, election: u32) -> Results {
+        Results {
+            candidates: Self::candidates_of(env, election, APPROVED_CANDIDATES),
+            abstentions: env
+                .storage()
+                .persistent()
+                .get(&(ABSTENTIONS, election))
+                .unwrap_or(0),
+            turnout: Self::turnout(env, election),
+            eligible: Self::list_len(env, election, APPROVED_VOTERS),
+        }
+    }
 
-            if candidate.ipfs != String::from_str(&env, "NotFound") {
-                candidates.push_back(candidate);
+    /// Rejects approval or ranked ballots that are empty, repeat a
+    /// candidate or name one who is not approved.
+    fn check_choices(env: &Env, election: u32, choices: &Vec<Address>) {
+        if choices.is_empty() {
+            panic_with_error!(env, Error::InvalidBallot);
+        }
+
+        let mut seen: Vec<Address> = vec![env];
+        for address in choices.iter() {
+            let candidate = Self::candidate_of(env, election, address.clone());
+            if candidate.status != APPROVED || seen.contains(&address) {
+                panic_with_error!(env, Error::InvalidBallot);
+            }
+            seen.push_back(address);
+        }
+    }
+
+    fn add_vote(env: &Env, election: u32, address: Address) {
+        let key = Candidates::Candidate(election, address);
+        let mut candidate: Candidate = env.storage().persistent().get(&key).unwrap();
+        candidate.vote_count = Self::ensure(
+            env,
+            candidate.vote_count.checked_add(1).ok_or(Error::Overflow),
+        );
+        env.storage().persistent().set(&key, &candidate);
+    }
+
+    fn remove_vote(env: &Env, election: u32, address: Address) {
+        let key = Candidates::Candidate(election, address);
+        let mut candidate: Candidate = env.storage().persistent().get(&key).unwrap();
+        candidate.vote_count = Self::ensure(
+            env,
+            candidate.vote_count.checked_sub(1).ok_or(Error::Overflow),
+        );
+        env.storage().persistent().set(&key, &candidate);
+    }
+
+    fn questions(env: &Env, election: u32) -> Vec<Question> {
+        env.storage()
+            .persistent()
+            .get(&(QUESTIONS, election))
+            .unwrap_or(vec![env])
+    }
+
+    fn question_tally(env: &Env, election: u32, question: u32) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&Questions::Tally(election, question))
+            .unwrap_or_else(|| panic_with_error!(env, Error::NotFound))
+    }
+
+    fn withdrawal_policy(env: &Env, election: u32) -> WithdrawalPolicy {
+        env.storage()
+            .persistent()
+            .get(&(WITHDRAWAL_POLICY, election))
+            .unwrap_or(WithdrawalPolicy::VoidVotes)
+    }
+
+    fn withdraw(env: &Env, address: Address, message: String) {
+        Self::ensure(env, validation::message(&message));
+
+        let election = Self::current_election(env);
+        let end_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&(END_TIME, election))
+            .unwrap_or(0);
+        if end_time != 0 && env.ledger().timestamp() > end_time {
+            panic_with_error!(env, Error::VotingEnded);
+        }
+
+        let mut candidate = Self::existing_candidate(env, election, address.clone());
+        if candidate.status != PENDING && candidate.status != APPROVED {
+            panic_with_error!(env, Error::InvalidStatus);
+        }
+
+        candidate.status = WITHDRAWN;
+        candidate.message = message;
+
+        env.storage().persistent().set(
+            &Candidates::Candidate(election, address.clone()),
+            &candidate,
+        );
+        Self::remove_from_list(env, election, APPROVED_CANDIDATES, &address);
+        env.events()
+            .publish((symbol_short!("candidate"), WITHDRAWN), address);
+    }
+
+    /// A ballot is void when it went to a candidate who has since withdrawn
+    /// and the election lets those voters vote again.
+    fn ballot_voided(env: &Env, election: u32, voter: Address) -> bool {
+        if Self::withdrawal_policy(env, election) != WithdrawalPolicy::ReenableVoters {
+            return false;
+        }
+
+        match env
+            .storage()
+            .persistent()
+            .get(&Ballots::Ballot(election, voter))
+        {
+            Some(Ballot::Candidate(c)) => Self::candidate_of(env, election, c).status == WITHDRAWN,
+            _ => false,
+        }
+    }
+
+    fn add_voter(
+        env: &Env,
+        name: String,
+        ipfs: String,
+        address: Address,
+        status: Symbol,
+        message: String,
+    ) {
+        let election = Self::current_election(env);
+        let mut registered: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(REGISTERED_VOTERS, election))
+            .unwrap_or(vec![env]);
+        let mut approved: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_VOTERS, election))
+            .unwrap_or(vec![env]);
+
+        let approving = status == APPROVED;
+        let queued = env
+            .storage()
+            .persistent()
+            .get(&Voters::Voter(election, address.clone()))
+            .filter(|voter: &Voter| voter.status == PENDING);
+
+        let result = match queued {
+            // A member already waiting in the manual queue who then shows
+            // they are eligible is approved in place, not turned away.
+            Some(voter) if approving => {
+                Self::approve_queued_voter(env, election, voter, name, ipfs, message, &mut approved)
+            }
+            _ => {
+                let voter = Voter {
+                    voter_address: address,
+                    name,
+                    ipfs,
+                    profile: Profile::empty(env),
+                    has_voted: false,
+                    message,
+                    register_id: 0,
+                    status,
+                };
+                Self::try_add_voter(env, election, voter, &mut registered, &mut approved)
+            }
+        };
+        if let Err(e) = result {
+            panic_with_error!(env, e);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(REGISTERED_VOTERS, election), &registered);
+        if approving {
+            env.storage()
+                .persistent()
+                .set(&(APPROVED_VOTERS, election), &approved);
+        }
+    }
+
+    /// Approves a pending voter with the details they registered again
+    /// with, keeping their `register_id`. The caller is responsible for
+    /// persisting `approved`.
+    fn approve_queued_voter(
+        env: &Env,
+        election: u32,
+        mut voter: Voter,
+        name: String,
+        ipfs: String,
+        message: String,
+        approved: &mut Vec<Address>,
+    ) -> Result<(), Error> {
+        validation::details(&name, &ipfs)?;
+
+        let address = voter.voter_address.clone();
+        Self::record_revision(
+            env,
+            &Voters::VoterHistory(election, address.clone()),
+            voter.name,
+            voter.ipfs,
+            voter.profile.clone(),
+            address.clone(),
+        );
+        voter.name = name;
+        voter.ipfs = ipfs;
+        voter.status = APPROVED;
+        voter.message = message;
+        env.storage()
+            .persistent()
+            .set(&Voters::Voter(election, address.clone()), &voter);
+
+        approved.push_back(address.clone());
+        env.events()
+            .publish((symbol_short!("voter"), APPROVED), address);
+
+        Ok(())
+    }
+
+    /// Stores a new voter record under the next register id and adds it to
+    /// the in-memory rolls. The caller is responsible for persisting them.
+    fn try_add_voter(
+        env: &Env,
+        election: u32,
+        mut voter: Voter,
+        registered: &mut Vec<Address>,
+        approved: &mut Vec<Address>,
+    ) -> Result<(), Error> {
+        validation::details(&voter.name, &voter.ipfs)?;
+
+        let address = voter.voter_address.clone();
+        let voter_id_key = Voters::Voter(election, address.clone());
+
+        if env.storage().persistent().has(&voter_id_key) {
+            return Err(Error::AlreadyRegistered);
+        }
+
+        let id = Self::next_id(env, &(VOTER_ID_COUNTER, election))?;
+        voter.register_id = id;
+
+        env.storage().persistent().set(&voter_id_key, &voter);
+        env.storage()
+            .persistent()
+            .set(&Voters::VoterById(election, id), &address);
+
+        registered.push_back(address.clone());
+        if voter.status == APPROVED {
+            approved.push_back(address.clone());
+            env.events()
+                .publish((symbol_short!("voter"), APPROVED), address);
+        }
+
+        Ok(())
+    }
+
+    fn decide_voter(env: &Env, address: Address, message: String, status: Symbol) {
+        let election = Self::current_election(env);
+        let mut approved: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_VOTERS, election))
+            .unwrap_or(vec![env]);
+
+        if let Err(e) =
+            Self::try_decide_voter(env, election, address, message, &status, &mut approved)
+        {
+            panic_with_error!(env, e);
+        }
+
+        if status == APPROVED {
+            env.storage()
+                .persistent()
+                .set(&(APPROVED_VOTERS, election), &approved);
+        }
+    }
+
+    fn decide_voters(env: &Env, decisions: Vec<(Address, String)>, status: Symbol) -> Vec<Outcome> {
+        if decisions.len() > MAX_BATCH_SIZE {
+            panic_with_error!(env, Error::BatchTooLarge);
+        }
+
+        let election = Self::current_election(env);
+        let mut approved: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_VOTERS, election))
+            .unwrap_or(vec![env]);
+
+        let mut outcomes: Vec<Outcome> = vec![env];
+        for (address, message) in decisions {
+            outcomes.push_back(
+                match Self::try_decide_voter(
+                    env,
+                    election,
+                    address,
+                    message,
+                    &status,
+                    &mut approved,
+                ) {
+                    Ok(()) => Outcome::Applied,
+                    Err(e) => Outcome::Failed(e as u32),
+                },
+            );
+        }
+
+        if status == APPROVED {
+            env.storage()
+                .persistent()
+                .set(&(APPROVED_VOTERS, election), &approved);
+        }
+
+        outcomes
+    }
+
+    /// Moves a pending voter to `status`, adding them to `approved` when
+    /// approving. The caller is responsible for persisting `approved`.
+    fn try_decide_voter(
+        env: &Env,
+        election: u32,
+        address: Address,
+        message: String,
+        status: &Symbol,
+        approved: &mut Vec<Address>,
+    ) -> Result<(), Error> {
+        validation::message(&message)?;
+
+        let key = Voters::Voter(election, address.clone());
+        let mut voter: Voter = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NotFound)?;
+        if voter.status != PENDING {
+            return Err(Error::InvalidStatus);
+        }
+
+        voter.status = status.clone();
+        voter.message = message;
+        env.storage().persistent().set(&key, &voter);
+
+        if *status == APPROVED {
+            approved.push_back(address.clone());
+        }
+        env.events()
+            .publish((symbol_short!("voter"), status.clone()), address);
+
+        Ok(())
+    }
+
+    fn decide_candidate(env: &Env, address: Address, message: String, status: Symbol) {
+        let election = Self::current_election(env);
+        let mut approved: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_CANDIDATES, election))
+            .unwrap_or(vec![env]);
+
+        if let Err(e) =
+            Self::try_decide_candidate(env, election, address, message, &status, &mut approved)
+        {
+            panic_with_error!(env, e);
+        }
+
+        if status == APPROVED {
+            env.storage()
+                .persistent()
+                .set(&(APPROVED_CANDIDATES, election), &approved);
+        }
+    }
+
+    fn decide_candidates(
+        env: &Env,
+        decisions: Vec<(Address, String)>,
+        status: Symbol,
+    ) -> Vec<Outcome> {
+        if decisions.len() > MAX_BATCH_SIZE {
+            panic_with_error!(env, Error::BatchTooLarge);
+        }
+
+        let election = Self::current_election(env);
+        let mut approved: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_CANDIDATES, election))
+            .unwrap_or(vec![env]);
+
+        let mut outcomes: Vec<Outcome> = vec![env];
+        for (address, message) in decisions {
+            outcomes.push_back(
+                match Self::try_decide_candidate(
+                    env,
+                    election,
+                    address,
+                    message,
+                    &status,
+                    &mut approved,
+                ) {
+                    Ok(()) => Outcome::Applied,
+                    Err(e) => Outcome::Failed(e as u32),
+                },
+            );
+        }
+
+        if status == APPROVED {
+            env.storage()
+                .persistent()
+                .set(&(APPROVED_CANDIDATES, election), &approved);
+        }
+
+        outcomes
+    }
+
+    fn try_decide_candidate(
+        env: &Env,
+        election: u32,
+        address: Address,
+        message: String,
+        status: &Symbol,
+        approved: &mut Vec<Address>,
+    ) -> Result<(), Error> {
+        validation::message(&message)?;
+
+        let key = Candidates::Candidate(election, address.clone());
+        let mut candidate: Candidate = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(Error::NotFound)?;
+        if candidate.status != PENDING {
+            return Err(Error::InvalidStatus);
+        }
+
+        candidate.status = status.clone();
+        candidate.message = message;
+        env.storage().persistent().set(&key, &candidate);
+
+        if *status == APPROVED {
+            approved.push_back(address.clone());
+        }
+        env.events()
+            .publish((symbol_short!("candidate"), status.clone()), address);
+
+        Ok(())
+    }
+
+    /// Appends the outgoing name/ipfs/profile to a record's history,
+    /// dropping the oldest entry once `MAX_REVISIONS` is reached.
+    fn record_revision<K>(
+        env: &Env,
+        key: &K,
+        name: String,
+        ipfs: String,
+        profile: Profile,
+        changed_by: Address,
+    ) where
+        K: IntoVal<Env, Val>,
+    {
+        let mut history: Vec<Revision> = env.storage().persistent().get(key).unwrap_or(vec![env]);
+        if history.len() >= MAX_REVISIONS {
+            history.pop_front();
+        }
+
+        history.push_back(Revision {
+            name,
+            ipfs,
+            profile,
+            changed_at: env.ledger().timestamp(),
+            changed_by,
+        });
+        env.storage().persistent().set(key, &history);
+    }
+
+    fn ensure<T>(env: &Env, result: Result<T, Error>) -> T {
+        result.unwrap_or_else(|e| panic_with_error!(env, e))
+    }
+
+    /// The candidate's details with any staged change applied, as the base
+    /// for a further change.
+    fn candidate_draft(env: &Env, election: u32, candidate: &Candidate) -> ProfileUpdate {
+        let pending = Candidates::PendingUpdate(election, candidate.candidate_address.clone());
+        match env.storage().persistent().get(&pending) {
+            Some(update) if candidate.status == APPROVED => update,
+            _ => ProfileUpdate {
+                name: candidate.name.clone(),
+                ipfs: candidate.ipfs.clone(),
+                profile: candidate.profile.clone(),
+                submitted_at: env.ledger().timestamp(),
+            },
+        }
+    }
+
+    /// Stages `change` for an approved candidate and applies it directly
+    /// otherwise.
+    fn change_candidate(env: &Env, election: u32, mut candidate: Candidate, change: ProfileUpdate) {
+        let addr = candidate.candidate_address.clone();
+
+        if candidate.status == APPROVED {
+            let update = ProfileUpdate {
+                submitted_at: env.ledger().timestamp(),
+                ..change
+            };
+            env.storage()
+                .persistent()
+                .set(&Candidates::PendingUpdate(election, addr.clone()), &update);
+            env.events().publish(
+                (symbol_short!("candidate"), symbol_short!("UpdStaged")),
+                addr,
+            );
+            return;
+        }
+
+        Self::record_revision(
+            env,
+            &Candidates::CandidateHistory(election, addr.clone()),
+            candidate.name,
+            candidate.ipfs,
+            candidate.profile,
+            addr.clone(),
+        );
+        candidate.name = change.name;
+        candidate.ipfs = change.ipfs;
+        candidate.profile = change.profile;
+
+        env.storage()
+            .persistent()
+            .set(&Candidates::Candidate(election, addr), &candidate)
+    }
+
+    fn list_len(env: &Env, election: u32, list: Symbol) -> u32 {
+        env.storage()
+            .persistent()
+            .get::<_, Vec<Address>>(&(list, election))
+            .map(|l| l.len())
+            .unwrap_or(0)
+    }
+
+    fn extend_if_present<K>(env: &Env, key: &K)
+    where
+        K: IntoVal<Env, Val>,
+    {
+        let storage = env.storage().persistent();
+        if storage.has(key) {
+            let max_ttl = env.storage().max_ttl();
+            storage.extend_ttl(key, max_ttl - DAY_IN_LEDGERS, max_ttl);
+        }
+    }
+
+    pub fn init(env: Env, owner_address: Address) {
+        if env.storage().persistent().has(&OWNER) {
+            panic_with_error!(&env, Error::AlreadyInitialized);
+        }
+        let election = Self::current_election(&env);
+        env.storage().persistent().set(&OWNER, &owner_address);
+        env.storage().persistent().set(&ELECTION_ID, &election);
+        env.storage()
+            .persistent()
+            .set(&(VOTER_ID_COUNTER, election), &1u32);
+        env.storage()
+            .persistent()
+            .set(&(CANDIDATE_ID_COUNTER, election), &1u32);
+        env.storage().persistent().set(&VERSION, &CONTRACT_VERSION);
+    }
+
+    /// Replaces the contract's code, keeping its storage. Not allowed while
+    /// a voting window is open. Call `migrate` once the new code is live.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>, address: Address) {
+        Self::owner_only(&env, address);
+        if Self::voting_open(&env, Self::current_election(&env)) {
+            panic_with_error!(&env, Error::VotingInProgress);
+        }
+
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        env.events().publish(
+            (symbol_short!("contract"), symbol_short!("upgraded")),
+            new_wasm_hash,
+        );
+    }
+
+    /// Moves storage from the stored version up to `CONTRACT_VERSION` and
+    /// returns the new version.
+    pub fn migrate(env: Env, address: Address) -> u32 {
+        Self::owner_only(&env, address);
+
+        let version = Self::version(&env);
+        if version >= CONTRACT_VERSION {
+            panic_with_error!(&env, Error::AlreadyMigrated);
+        }
+        // Version 2 is the first layout that can be upgraded in place, so
+        // there is nothing to convert yet. Steps from each later version's
+        // predecessor go here.
+        env.storage().persistent().set(&VERSION, &CONTRACT_VERSION);
+        CONTRACT_VERSION
+    }
+
+    pub fn get_version(env: Env) -> u32 {
+        Self::version(&env)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn registerVoter(env: Env, name: String, ipfs: String, address: Address) {
+        address.require_auth();
+
+        Self::add_voter(
+            &env,
+            name,
+            ipfs,
+            address,
+            PENDING,
+            String::from_str(&env, PENDING_MESSAGE),
+        );
+    }
+
+    /// Marks addresses as pre-verified for the current election, so they
+    /// can register themselves with `register_allowlisted` and skip the
+    /// approval queue. Pass `allowed = false` to take addresses off again.
+    pub fn set_allowlist(env: Env, voters: Vec<Address>, allowed: bool, address: Address) {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        for voter in voters {
+            let key = Eligibility::Allowed(election, voter);
+            if allowed {
+                env.storage().persistent().set(&key, &true);
+            } else {
+                env.storage().persistent().remove(&key);
+            }
+        }
+    }
+
+    pub fn is_allowlisted(env: Env, voter: Address) -> bool {
+        let election = Self::current_election(&env);
+        env.storage()
+            .persistent()
+            .get(&Eligibility::Allowed(election, voter))
+            .unwrap_or(false)
+    }
+
+    /// Sets the Merkle root of eligible addresses for the current election;
+    /// see the `eligibility` module for the tree layout.
+    pub fn set_eligibility_root(env: Env, root: BytesN<32>, address: Address) {
+        Self::owner_only(&env, address);
+        let election = Self::current_election(&env);
+        env.storage()
+            .persistent()
+            .set(&(ELIGIBILITY_ROOT, election), &root);
+    }
+
+    pub fn get_eligibility_root(env: Env) -> Option<BytesN<32>> {
+        let election = Self::current_election(&env);
+        env.storage()
+            .persistent()
+            .get(&(ELIGIBILITY_ROOT, election))
+    }
+
+    /// Sets the ed25519 key whose signatures `register_with_voucher`
+    /// accepts. Vouchers are bound to this contract and to one election.
+    pub fn set_voucher_signer(env: Env, public_key: BytesN<32>, address: Address) {
+        Self::owner_only(&env, address);
+        env.storage().persistent().set(&VOUCHER_SIGNER, &public_key);
+    }
+
+    pub fn get_voucher_signer(env: Env) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&VOUCHER_SIGNER)
+    }
+
+    pub fn register_allowlisted(env: Env, name: String, ipfs: String, address: Address) {
+        address.require_auth();
+
+        if !Self::is_allowlisted(env.clone(), address.clone()) {
+            panic_with_error!(&env, Error::NotEligible);
+        }
+
+        Self::add_voter(
+            &env,
+            name,
+            ipfs,
+            address,
+            APPROVED,
+            String::from_str(&env, "Approved from the allowlist"),
+        );
+    }
+
+    pub fn register_with_proof(
+        env: Env,
+        name: String,
+        ipfs: String,
+        address: Address,
+        proof: Vec<BytesN<32>>,
+    ) {
+        address.require_auth();
+
+        let root = Self::get_eligibility_root(env.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotEligible));
+        let leaf = eligibility::leaf(&env, &address);
+        if !eligibility::verify_proof(&env, &root, leaf, &proof) {
+            panic_with_error!(&env, Error::NotEligible);
+        }
+
+        Self::add_voter(
+            &env,
+            name,
+            ipfs,
+            address,
+            APPROVED,
+            String::from_str(&env, "Approved by eligibility proof"),
+        );
+    }
+
+    pub fn register_with_voucher(
+        env: Env,
+        name: String,
+        ipfs: String,
+        address: Address,
+        signature: BytesN<64>,
+    ) {
+        address.require_auth();
+
+        let signer = Self::get_voucher_signer(env.clone())
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotEligible));
+        let election = Self::current_election(&env);
+        let message = eligibility::voucher_message(&env, election, &address);
+        env.crypto().ed25519_verify(&signer, &message, &signature);
+
+        Self::add_voter(
+            &env,
+            name,
+            ipfs,
+            address,
+            APPROVED,
+            String::from_str(&env, "Approved by voucher"),
+        );
+    }
+
+    pub fn register_candidate(env: Env, name: String, ipfs: String, address: Address) {
+        address.require_auth();
+        Self::ensure(&env, validation::details(&name, &ipfs));
+
+        let election = Self::current_election(&env);
+        let candidate_id_key = Candidates::Candidate(election, address.clone());
+
+        if env.storage().persistent().has(&candidate_id_key) {
+            panic_with_error!(&env, Error::AlreadyRegistered);
+        }
+
+        let id = Self::ensure(&env, Self::next_id(&env, &(CANDIDATE_ID_COUNTER, election)));
+
+        let new_candidate = Candidate {
+            candidate_address: address.clone(),
+            name,
+            ipfs,
+            profile: Profile::empty(&env),
+            message: String::from_str(&env, PENDING_MESSAGE),
+            register_id: id,
+            status: PENDING,
+            vote_count: 0,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&candidate_id_key, &new_candidate);
+        env.storage()
+            .persistent()
+            .set(&Candidates::CandidateById(election, id), &address);
+
+        let mut registered_voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(REGISTERED_CANDIDATES, election))
+            .unwrap_or(vec![&env]);
+
+        registered_voters.push_back(address);
+
+        env.storage()
+            .persistent()
+            .set(&(REGISTERED_CANDIDATES, election), &registered_voters);
+    }
+
+    /// Puts a rejected or revoked voter back in the approval queue with
+    /// fresh details. The record keeps its `register_id` and `has_voted`,
+    /// so a voter who already voted cannot vote again once re-approved.
+    pub fn reapply_voter(env: Env, name: String, ipfs: String, address: Address) {
+        address.require_auth();
+        Self::ensure(&env, validation::details(&name, &ipfs));
+
+        let election = Self::current_election(&env);
+        let mut voter = Self::existing_voter(&env, election, address.clone());
+        if voter.status != REJECTED && voter.status != REVOKED {
+            panic_with_error!(&env, Error::InvalidStatus);
+        }
+
+        Self::record_revision(
+            &env,
+            &Voters::VoterHistory(election, address.clone()),
+            voter.name,
+            voter.ipfs,
+            voter.profile.clone(),
+            address.clone(),
+        );
+        voter.name = name;
+        voter.ipfs = ipfs;
+        voter.status = PENDING;
+        voter.message = String::from_str(&env, PENDING_MESSAGE);
+
+        env.storage()
+            .persistent()
+            .set(&Voters::Voter(election, address), &voter);
+    }
+
+    /// Puts a rejected candidate back in the approval queue with fresh
+    /// details, keeping their `register_id`. Withdrawn candidates cannot
+    /// come back in the same election.
+    pub fn reapply_candidate(env: Env, name: String, ipfs: String, address: Address) {
+        address.require_auth();
+        Self::ensure(&env, validation::details(&name, &ipfs));
+
+        let election = Self::current_election(&env);
+        let mut candidate = Self::existing_candidate(&env, election, address.clone());
+        Self::require_status(&env, &candidate.status, REJECTED);
+
+        Self::record_revision(
+            &env,
+            &Candidates::CandidateHistory(election, address.clone()),
+            candidate.name,
+            candidate.ipfs,
+            candidate.profile.clone(),
+            address.clone(),
+        );
+        candidate.name = name;
+        candidate.ipfs = ipfs;
+        candidate.status = PENDING;
+        candidate.message = String::from_str(&env, PENDING_MESSAGE);
+
+        env.storage()
+            .persistent()
+            .set(&Candidates::Candidate(election, address), &candidate);
+    }
+
+    pub fn approve_voter(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::decide_voter(&env, address, message, APPROVED);
+    }
+
+    pub fn approve_candidate(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::decide_candidate(&env, address, message, APPROVED);
+    }
+
+    /// Turns down a pending registration.
+    pub fn reject_voter(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::decide_voter(&env, address, message, REJECTED);
+    }
+
+    /// Turns down a pending candidacy.
+    pub fn reject_candidate(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::decide_candidate(&env, address, message, REJECTED);
+    }
+
+    /// Approves up to `MAX_BATCH_SIZE` pending voters in one transaction.
+    /// Items that cannot be approved are reported in the returned outcomes,
+    /// in input order, and do not stop the rest of the batch.
+    pub fn approve_voters(env: Env, decisions: Vec<(Address, String)>) -> Vec<Outcome> {
+        Self::owner_auth(&env);
+        Self::decide_voters(&env, decisions, APPROVED)
+    }
+
+    /// Registers and approves up to `MAX_IMPORT_SIZE` members from an
+    /// off-chain roll as `(address, name, ipfs)` entries. Addresses that
+    /// are already registered are reported and skipped.
+    pub fn import_voters(env: Env, entries: Vec<(Address, String, String)>) -> Vec<Outcome> {
+        Self::owner_auth(&env);
+
+        if entries.len() > MAX_IMPORT_SIZE {
+            panic_with_error!(&env, Error::BatchTooLarge);
+        }
+
+        let election = Self::current_election(&env);
+        let mut registered: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(REGISTERED_VOTERS, election))
+            .unwrap_or(vec![&env]);
+        let mut approved: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_VOTERS, election))
+            .unwrap_or(vec![&env]);
+
+        let mut outcomes: Vec<Outcome> = vec![&env];
+        for (address, name, ipfs) in entries {
+            let voter = Voter {
+                voter_address: address,
+                name,
+                ipfs,
+                profile: Profile::empty(&env),
+                has_voted: false,
+                message: String::from_str(&env, "Imported from the membership roll"),
+                register_id: 0,
+                status: APPROVED,
+            };
+            outcomes.push_back(
+                match Self::try_add_voter(&env, election, voter, &mut registered, &mut approved) {
+                    Ok(()) => Outcome::Applied,
+                    Err(e) => Outcome::Failed(e as u32),
+                },
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(REGISTERED_VOTERS, election), &registered);
+        env.storage()
+            .persistent()
+            .set(&(APPROVED_VOTERS, election), &approved);
+
+        outcomes
+    }
+
+    /// Batch counterpart of `reject_voter`; see `approve_voters`.
+    pub fn reject_voters(env: Env, decisions: Vec<(Address, String)>) -> Vec<Outcome> {
+        Self::owner_auth(&env);
+        Self::decide_voters(&env, decisions, REJECTED)
+    }
+
+    /// Batch counterpart of `approve_candidate`; see `approve_voters`.
+    pub fn approve_candidates(env: Env, decisions: Vec<(Address, String)>) -> Vec<Outcome> {
+        Self::owner_auth(&env);
+        Self::decide_candidates(&env, decisions, APPROVED)
+    }
+
+    /// Batch counterpart of `reject_candidate`; see `approve_voters`.
+    pub fn reject_candidates(env: Env, decisions: Vec<(Address, String)>) -> Vec<Outcome> {
+        Self::owner_auth(&env);
+        Self::decide_candidates(&env, decisions, REJECTED)
+    }
+
+    /// Withdraws the right to vote from an approved voter. A ballot the voter
+    /// already cast was valid when it was cast and stays counted; `has_voted`
+    /// is kept so a later re-approval cannot be used to vote twice.
+    pub fn revoke_voter(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::ensure(&env, validation::message(&message));
+
+        let election = Self::current_election(&env);
+        let mut voter = Self::existing_voter(&env, election, address.clone());
+        Self::require_status(&env, &voter.status, APPROVED);
+
+        voter.status = REVOKED;
+        voter.message = message;
+
+        env.storage()
+            .persistent()
+            .set(&Voters::Voter(election, address.clone()), &voter);
+        Self::remove_from_list(&env, election, APPROVED_VOTERS, &address);
+        env.events()
+            .publish((symbol_short!("voter"), REVOKED), address);
+    }
+
+    /// Takes a pending or approved candidate off the ballot on the
+    /// registrar's initiative. Ballots already cast for them are handled
+    /// according to the election's `WithdrawalPolicy`.
+    pub fn withdraw_candidate(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::withdraw(&env, address, message);
+    }
+
+    /// Lets a candidate leave the election on their own. Same effect as
+    /// `withdraw_candidate`, but authorised by the candidate.
+    pub fn withdraw_candidacy(env: Env, candidate: Address) {
+        candidate.require_auth();
+        Self::withdraw(
+            &env,
+            candidate,
+            String::from_str(&env, "Candidacy withdrawn by the candidate"),
+        );
+    }
+
+    /// Chooses what happens to ballots cast for a candidate who withdraws
+    /// while voting is open. Can only be changed before voting starts.
+    pub fn set_withdrawal_policy(env: Env, policy: WithdrawalPolicy, address: Address) {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        Self::before_voting(&env, election);
+
+        env.storage()
+            .persistent()
+            .set(&(WITHDRAWAL_POLICY, election), &policy);
+    }
+
+    pub fn get_withdrawal_policy(env: Env) -> WithdrawalPolicy {
+        Self::withdrawal_policy(&env, Self::current_election(&env))
+    }
+
+    /// Lets voters replace their ballot any number of times until voting
+    /// ends; only the last one counts. Off by default. This also applies to
+    /// referendum answers.
+    pub fn set_allow_revote(env: Env, allowed: bool, address: Address) {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        Self::before_voting(&env, election);
+
+        env.storage()
+            .persistent()
+            .set(&(REVOTE, election), &allowed);
+    }
+
+    pub fn get_allow_revote(env: Env) -> bool {
+        Self::revote_allowed(&env, Self::current_election(&env))
+    }
+
+    /// Number of candidates the current election fills. Defaults to 1.
+    pub fn set_seats(env: Env, seats: u32, address: Address) {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        Self::before_voting(&env, election);
+        if seats == 0 {
+            panic_with_error!(&env, Error::InvalidSeats);
+        }
+
+        env.storage().persistent().set(&(SEATS, election), &seats);
+    }
+
+    pub fn get_seats(env: Env) -> u32 {
+        Self::seats(&env, Self::current_election(&env))
+    }
+
+    /// Defaults to `Plurality`.
+    pub fn set_voting_method(env: Env, method: VotingMethod, address: Address) {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        Self::before_voting(&env, election);
+
+        env.storage()
+            .persistent()
+            .set(&(VOTING_METHOD, election), &method);
+    }
+
+    pub fn get_voting_method(env: Env) -> VotingMethod {
+        Self::voting_method(&env, Self::current_election(&env))
+    }
+
+    pub fn set_voting_period(env: Env, start_time: u64, end_time: u64, address: Address) {
+        Self::owner_only(&env, address.clone());
+
+        assert!(start_time < end_time, "Start time must be before end time.");
+
+        let election = Self::current_election(&env);
+        if Self::is_finalized(&env, election) {
+            panic_with_error!(&env, Error::ElectionFinalized);
+        }
+        // Once voting has started only the end time may change.
+        let current: Option<u64> = env.storage().persistent().get(&(START_TIME, election));
+        if current != Some(start_time) {
+            let started = current.is_some_and(|start| env.ledger().timestamp() >= start)
+                || Self::last_receipt(&env, election).is_some();
+            if started {
+                panic_with_error!(&env, Error::VotingStarted);
             }
         }
+        env.storage()
+            .persistent()
+            .set(&(START_TIME, election), &start_time);
+        env.storage()
+            .persistent()
+            .set(&(END_TIME, election), &end_time);
+    }
+
+    pub fn get_all_registered_voters(env: Env) -> Vec<Voter> {
+        Self::voters_of(&env, Self::current_election(&env), REGISTERED_VOTERS)
+    }
+
+    pub fn get_all_registered_candidates(env: Env) -> Vec<Candidate> {
+        Self::candidates_of(&env, Self::current_election(&env), REGISTERED_CANDIDATES)
+    }
+
+    pub fn get_all_approved_candidates(env: Env) -> Vec<Candidate> {
+        Self::candidates_of(&env, Self::current_election(&env), APPROVED_CANDIDATES)
+    }
+
+    pub fn get_all_approved_voters(env: Env) -> Vec<Voter> {
+        Self::voters_of(&env, Self::current_election(&env), APPROVED_VOTERS)
+    }
+
+    /// Looks a voter up by `register_id`. Voter and candidate ids are
+    /// numbered separately from 1 in each election and never reused.
+    pub fn get_voter_by_id(env: Env, id: u32) -> Voter {
+        let election = Self::current_election(&env);
+        let address = Self::voter_address(&env, election, id);
+        Self::existing_voter(&env, election, address)
+    }
+
+    /// Looks a candidate up by the `register_id` printed on ballots.
+    pub fn get_candidate_by_id(env: Env, id: u32) -> Candidate {
+        let election = Self::current_election(&env);
+        let address = Self::candidate_address(&env, election, id);
+        Self::existing_candidate(&env, election, address)
+    }
+
+    pub fn get_candidate(env: Env, addr: Address) -> Candidate {
+        Self::candidate_of(&env, Self::current_election(&env), addr)
+    }
+
+    pub fn get_voter(env: Env, addr: Address) -> Voter {
+        Self::voter_of(&env, Self::current_election(&env), addr)
+    }
+
+    pub fn update_voter(env: Env, name: String, ipfs: String, addr: Address) {
+        addr.require_auth();
+        Self::ensure(&env, validation::details(&name, &ipfs));
+
+        let election = Self::current_election(&env);
+        let key = Voters::Voter(election, addr.clone());
+        let mut voter = Self::existing_voter(&env, election, addr.clone());
+
+        Self::record_revision(
+            &env,
+            &Voters::VoterHistory(election, addr.clone()),
+            voter.name,
+            voter.ipfs,
+            voter.profile.clone(),
+            addr,
+        );
+        voter.name = name;
+        voter.ipfs = ipfs;
+
+        env.storage().persistent().set(&key, &voter)
+    }
+
+    /// Changes a candidate's name/ipfs. While the candidate is approved the
+    /// change is only staged: the approved profile stays visible until a
+    /// registrar accepts it with `approve_candidate_update`.
+    pub fn update_candidate(env: Env, name: String, ipfs: String, addr: Address) {
+        addr.require_auth();
+        Self::ensure(&env, validation::details(&name, &ipfs));
+
+        let election = Self::current_election(&env);
+        let candidate = Self::existing_candidate(&env, election, addr.clone());
+        let mut change = Self::candidate_draft(&env, election, &candidate);
+        change.name = name;
+        change.ipfs = ipfs;
+
+        Self::change_candidate(&env, election, candidate, change);
+    }
+
+    /// Sets a candidate's structured profile, staged for review in the same
+    /// way as `update_candidate` while the candidate is approved.
+    pub fn set_candidate_profile(env: Env, profile: Profile, addr: Address) {
+        addr.require_auth();
+        Self::ensure(&env, validation::profile(&profile));
+
+        let election = Self::current_election(&env);
+        let candidate = Self::existing_candidate(&env, election, addr.clone());
+        let mut change = Self::candidate_draft(&env, election, &candidate);
+        change.profile = profile;
 
-        return candidates;
+        Self::change_candidate(&env, election, candidate, change);
     }
 
-    pub fn get_all_approved_candidates(env: Env) -> Vec<Candidate> {
-        let mut approved_candidates: Vec<Candidate> = vec![&env];
+    pub fn get_pending_candidate_update(env: Env, addr: Address) -> Option<ProfileUpdate> {
+        let election = Self::current_election(&env);
+        env.storage()
+            .persistent()
+            .get(&Candidates::PendingUpdate(election, addr))
+    }
 
-        let approved_address: Vec<Address> = env
+    /// Publishes a candidate's staged profile change.
+    pub fn approve_candidate_update(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::ensure(&env, validation::message(&message));
+
+        let election = Self::current_election(&env);
+        let update_key = Candidates::PendingUpdate(election, address.clone());
+        let update: ProfileUpdate = env
             .storage()
             .persistent()
-            .get(&APPROVED_CANDIDATES)
-            .unwrap_or(vec![&env]);
+            .get(&update_key)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::NotFound));
+        let mut candidate = Self::existing_candidate(&env, election, address.clone());
+
+        Self::record_revision(
+            &env,
+            &Candidates::CandidateHistory(election, address.clone()),
+            candidate.name,
+            candidate.ipfs,
+            candidate.profile,
+            address.clone(),
+        );
+        candidate.name = update.name;
+        candidate.ipfs = update.ipfs;
+        candidate.profile = update.profile;
+        candidate.message = message;
 
-        for a in approved_address.iter() {
-            let key = Candidates::Candidate(a.clone());
-            let candidate = env.storage().persistent().get(&key).unwrap_or(Candidate {
-                ipfs: String::from_str(&env, "NotFound"),
-                message: String::from_str(&env, ""),
-                name: String::from_str(&env, ""),
-                register_id: U256::from_u32(&env, 0),
-                status: REJECTED,
-                candidate_address: a,
-                vote_count: U256::from_u32(&env, 0),
-            });
+        env.storage().persistent().set(
+            &Candidates::Candidate(election, address.clone()),
+            &candidate,
+        );
+        env.storage().persistent().remove(&update_key);
+        env.events().publish(
+            (symbol_short!("candidate"), symbol_short!("UpdApprov")),
+            address,
+        );
+    }
 
-            if candidate.ipfs != String::from_str(&env, "NotFound") {
-                approved_candidates.push_back(candidate);
-            }
+    /// Discards a candidate's staged profile change.
+    pub fn reject_candidate_update(env: Env, address: Address, message: String) {
+        Self::owner_auth(&env);
+        Self::ensure(&env, validation::message(&message));
+
+        let election = Self::current_election(&env);
+        let update_key = Candidates::PendingUpdate(election, address.clone());
+        if !env.storage().persistent().has(&update_key) {
+            panic_with_error!(&env, Error::NotFound);
         }
+        let mut candidate = Self::existing_candidate(&env, election, address.clone());
+        candidate.message = message;
 
-        return approved_candidates;
+        env.storage().persistent().set(
+            &Candidates::Candidate(election, address.clone()),
+            &candidate,
+        );
+        env.storage().persistent().remove(&update_key);
+        env.events().publish(
+            (symbol_short!("candidate"), symbol_short!("UpdReject")),
+            address,
+        );
     }
 
-    pub fn get_all_approved_voters(env: Env) -> Vec<Voter> {
-        let mut approved_voters: Vec<Voter> = vec![&env];
+    pub fn set_voter_profile(env: Env, profile: Profile, addr: Address) {
+        addr.require_auth();
+        Self::ensure(&env, validation::profile(&profile));
+
+        let election = Self::current_election(&env);
+        let key = Voters::Voter(election, addr.clone());
+        let mut voter = Self::existing_voter(&env, election, addr.clone());
+
+        Self::record_revision(
+            &env,
+            &Voters::VoterHistory(election, addr.clone()),
+            voter.name.clone(),
+            voter.ipfs.clone(),
+            voter.profile,
+            addr,
+        );
+        voter.profile = profile;
 
-        let approved_address: Vec<Address> = env
-            .storage()
+        env.storage().persistent().set(&key, &voter)
+    }
+
+    /// Earlier versions of a voter's name, ipfs and profile, oldest first. Only the
+    /// last `MAX_REVISIONS` changes are kept.
+    pub fn get_voter_history(env: Env, addr: Address) -> Vec<Revision> {
+        let election = Self::current_election(&env);
+        env.storage()
             .persistent()
-            .get(&APPROVED_VOTERS)
-            .unwrap_or(vec![&env]);
+            .get(&Voters::VoterHistory(election, addr))
+            .unwrap_or(vec![&env])
+    }
 
-        for a in approved_address.iter() {
-            let key = Voters::Voter(a.clone());
-            let voter = env.storage().persistent().get(&key).unwrap_or(Voter {
-                ipfs: String::from_str(&env, "NotFound"),
-                message: String::from_str(&env, ""),
-                name: String::from_str(&env, ""),
-                register_id: U256::from_u32(&env, 0),
-                status: REJECTED,
-                voter_address: a,
-                has_voted: false,
-            });
+    /// Earlier versions of a candidate's name, ipfs and profile, oldest
+    /// first. Each entry says until when it was current and who replaced it.
+    pub fn get_candidate_history(env: Env, addr: Address) -> Vec<Revision> {
+        let election = Self::current_election(&env);
+        env.storage()
+            .persistent()
+            .get(&Candidates::CandidateHistory(election, addr))
+            .unwrap_or(vec![&env])
+    }
 
-            if voter.ipfs != String::from_str(&env, "NotFound") {
-                approved_voters.push_back(voter);
-            }
+    pub fn change_owner(env: Env, new_owner: Address, address: Address) {
+        Self::owner_only(&env, address.clone());
+        env.storage().persistent().set(&OWNER, &new_owner);
+    }
+
+    /// Closes the current election and opens a fresh one. Nothing is
+    /// deleted: the old rolls, records and tallies stay under their
+    /// election id and become read-only. Returns the archived id. Fails
+    /// while voting is open.
+    pub fn archive_election(env: Env, address: Address) -> u32 {
+        Self::owner_only(&env, address.clone());
+
+        let election = Self::current_election(&env);
+        if Self::voting_open(&env, election) {
+            panic_with_error!(&env, Error::VotingInProgress);
         }
+        let (ballot_root, ballots_cast) = if Self::is_finalized(&env, election) {
+            Self::ballot_root(&env, election)
+        } else {
+            Self::freeze(&env, election)
+        };
+        let archived = ArchivedElection {
+            election_id: election,
+            start_time: env
+                .storage()
+                .persistent()
+                .get(&(START_TIME, election))
+                .unwrap_or(0),
+            end_time: env
+                .storage()
+                .persistent()
+                .get(&(END_TIME, election))
+                .unwrap_or(0),
+            archived_at: env.ledger().timestamp(),
+            registered_voters: Self::list_len(&env, election, REGISTERED_VOTERS),
+            registered_candidates: Self::list_len(&env, election, REGISTERED_CANDIDATES),
+            approved_voters: Self::list_len(&env, election, APPROVED_VOTERS),
+            approved_candidates: Self::list_len(&env, election, APPROVED_CANDIDATES),
+            ballots_cast,
+            ballot_root,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&Elections::Archived(election), &archived);
+
+        let next = Self::ensure(&env, election.checked_add(1).ok_or(Error::Overflow));
+        env.storage().persistent().set(&ELECTION_ID, &next);
+        env.storage()
+            .persistent()
+            .set(&(VOTER_ID_COUNTER, next), &1u32);
+        env.storage()
+            .persistent()
+            .set(&(CANDIDATE_ID_COUNTER, next), &1u32);
 
-        return approved_voters;
+        election
     }
 
-    pub fn get_candidate(env: Env, addr: Address) -> Candidate {
-        let key = Candidates::Candidate(addr.clone());
-        let candidate = env.storage().persistent().get(&key).unwrap_or(Candidate {
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            name: String::from_str(&env, ""),
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            candidate_address: addr,
-            vote_count: U256::from_u32(&env, 0),
-        });
+    /// Closes the current election for good once voting has ended: the
+    /// ballot root is frozen and the voting period can no longer be changed.
+    /// Auditors can rebuild the root by replaying the `ballot` events, see
+    /// `receipt.rs`. Archiving an election finalizes it if needed.
+    pub fn finalize_election(env: Env, address: Address) -> BytesN<32> {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        if Self::is_finalized(&env, election) {
+            panic_with_error!(&env, Error::ElectionFinalized);
+        }
+        let end_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&(END_TIME, election))
+            .unwrap_or(0);
+        if end_time == 0 || env.ledger().timestamp() <= end_time {
+            panic_with_error!(&env, Error::VotingNotEnded);
+        }
 
-        return candidate;
+        Self::freeze(&env, election).0
     }
 
-    pub fn get_voter(env: Env, addr: Address) -> Voter {
-        let key = Voters::Voter(addr.clone());
-        let voter = env.storage().persistent().get(&key).unwrap_or(Voter {
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            name: String::from_str(&env, ""),
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            voter_address: addr,
-            has_voted: false,
-        });
+    pub fn is_election_finalized(env: Env) -> bool {
+        Self::is_finalized(&env, Self::current_election(&env))
+    }
 
-        return voter;
+    /// Root committing to every ballot cast in the current election so far,
+    /// in order. Equal to the hash of `get_last_receipt`, or 32 zero bytes
+    /// before the first ballot. Fixed once the election is finalized.
+    pub fn get_ballot_root(env: Env) -> BytesN<32> {
+        Self::ballot_root(&env, Self::current_election(&env)).0
     }
 
-    pub fn update_voter(env: Env, name: String, ipfs: String, addr: Address) {
-        let key = Voters::Voter(addr.clone());
-        let mut voter = env.storage().persistent().get(&key).unwrap_or(Voter {
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            name: String::from_str(&env, ""),
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            voter_address: addr,
-            has_voted: false,
-        });
+    /// Kept for existing clients; archives instead of deleting.
+    pub fn reset_contract(env: Env, address: Address) {
+        Self::archive_election(env, address);
+    }
 
-        assert_ne!(voter.ipfs, String::from_str(&env, "NotFound"));
+    pub fn get_current_election(env: Env) -> u32 {
+        Self::current_election(&env)
+    }
 
-        voter.name = name;
-        voter.ipfs = ipfs;
+    pub fn get_archived_election(env: Env, election_id: u32) -> ArchivedElection {
+        Self::archived_only(&env, election_id);
+        env.storage()
+            .persistent()
+            .get(&Elections::Archived(election_id))
+            .expect("Election is not archived")
+    }
 
-        env.storage().persistent().set(&key, &voter)
+    pub fn get_archived_voters(env: Env, election_id: u32) -> Vec<Voter> {
+        Self::archived_only(&env, election_id);
+        Self::voters_of(&env, election_id, REGISTERED_VOTERS)
     }
 
-    pub fn update_candidate(env: Env, name: String, ipfs: String, addr: Address) {
-        let key = Candidates::Candidate(addr.clone());
-        let mut candidate = env.storage().persistent().get(&key).unwrap_or(Candidate {
-            ipfs: String::from_str(&env, "NotFound"),
-            message: String::from_str(&env, ""),
-            name: String::from_str(&env, ""),
-            register_id: U256::from_u32(&env, 0),
-            status: REJECTED,
-            candidate_address: addr,
-            vote_count: U256::from_u32(&env, 0),
-        });
+    pub fn get_archived_candidates(env: Env, election_id: u32) -> Vec<Candidate> {
+        Self::archived_only(&env, election_id);
+        Self::candidates_of(&env, election_id, REGISTERED_CANDIDATES)
+    }
 
-        assert_ne!(candidate.ipfs, String::from_str(&env, "NotFound"));
+    pub fn get_archived_voter(env: Env, election_id: u32, addr: Address) -> Voter {
+        Self::archived_only(&env, election_id);
+        Self::voter_of(&env, election_id, addr)
+    }
 
-        candidate.name = name;
-        candidate.ipfs = ipfs;
+    pub fn get_archived_candidate(env: Env, election_id: u32, addr: Address) -> Candidate {
+        Self::archived_only(&env, election_id);
+        Self::candidate_of(&env, election_id, addr)
+    }
 
-        env.storage().persistent().set(&key, &candidate)
+    pub fn get_archived_voter_history(env: Env, election_id: u32, addr: Address) -> Vec<Revision> {
+        Self::archived_only(&env, election_id);
+        env.storage()
+            .persistent()
+            .get(&Voters::VoterHistory(election_id, addr))
+            .unwrap_or(vec![&env])
     }
 
-    pub fn change_owner(env: Env, new_owner: Address, address: Address) {
-        Self::owner_only(&env, address.clone());
-        env.storage().persistent().set(&OWNER, &new_owner);
+    pub fn get_archived_candidate_history(
+        env: Env,
+        election_id: u32,
+        addr: Address,
+    ) -> Vec<Revision> {
+        Self::archived_only(&env, election_id);
+        env.storage()
+            .persistent()
+            .get(&Candidates::CandidateHistory(election_id, addr))
+            .unwrap_or(vec![&env])
     }
 
-    pub fn reset_contract(env: Env, address: Address) {
-        Self::owner_only(&env, address.clone());
+    /// Persistent entries are evicted once their TTL runs out, and the
+    /// network caps a single extension at `max_ttl`. Anyone can call this
+    /// periodically to keep an archived election live for the retention
+    /// period; voter records and receipt chain entries are bumped `limit`
+    /// at a time from `start` so large rolls fit in one transaction. Every
+    /// call also bumps the contract itself and the keys shared by all
+    /// elections, without which no archive can be read. Evicted entries can
+    /// still be restored with a `RestoreFootprint` operation.
+    pub fn extend_archive_ttl(env: Env, election_id: u32, start: u32, limit: u32) {
+        Self::archived_only(&env, election_id);
+
+        let max_ttl = env.storage().max_ttl();
+        env.storage()
+            .instance()
+            .extend_ttl(max_ttl - DAY_IN_LEDGERS, max_ttl);
+        for key in [OWNER, ELECTION_ID, VERSION, VOUCHER_SIGNER] {
+            Self::extend_if_present(&env, &key);
+        }
 
-        let voters: Vec<Address> = env
+        Self::extend_if_present(&env, &Elections::Archived(election_id));
+        Self::extend_if_present(&env, &Elections::Finalized(election_id));
+        for list in [
+            REGISTERED_VOTERS,
+            REGISTERED_CANDIDATES,
+            APPROVED_VOTERS,
+            APPROVED_CANDIDATES,
+            START_TIME,
+            END_TIME,
+            WITHDRAWAL_POLICY,
+            ELIGIBILITY_ROOT,
+            SEATS,
+            VOTING_METHOD,
+            QUESTIONS,
+            ABSTENTIONS,
+            TURNOUT,
+            LAST_RECEIPT,
+        ] {
+            Self::extend_if_present(&env, &(list, election_id));
+        }
+
+        let questions = Self::questions(&env, election_id).len();
+        for q in 0..questions {
+            Self::extend_if_present(&env, &Questions::Tally(election_id, q));
+        }
+
+        let candidates: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&REGISTERED_VOTERS)
+            .get(&(REGISTERED_CANDIDATES, election_id))
             .unwrap_or(vec![&env]);
-
-        for v in voters {
-            let key = Voters::Voter(v);
-            env.storage().persistent().remove(&key);
+        for c in candidates {
+            Self::extend_if_present(&env, &Candidates::Candidate(election_id, c.clone()));
+            Self::extend_if_present(&env, &Candidates::CandidateHistory(election_id, c.clone()));
+            Self::extend_if_present(&env, &Candidates::PendingUpdate(election_id, c));
         }
 
-        let candidates: Vec<Address> = env
+        let voters: Vec<Address> = env
             .storage()
             .persistent()
-            .get(&REGISTERED_CANDIDATES)
+            .get(&(REGISTERED_VOTERS, election_id))
             .unwrap_or(vec![&env]);
+        let end = voters.len().min(start.saturating_add(limit));
+        for i in start..end {
+            let v = voters.get_unchecked(i);
+            Self::extend_if_present(&env, &Voters::Voter(election_id, v.clone()));
+            Self::extend_if_present(&env, &Voters::VoterHistory(election_id, v.clone()));
+            Self::extend_if_present(&env, &Ballots::Ballot(election_id, v.clone()));
+            Self::extend_if_present(&env, &Ballots::Receipt(election_id, v.clone()));
+            Self::extend_if_present(&env, &Ballots::Participated(election_id, v.clone()));
+            for q in 0..questions {
+                Self::extend_if_present(&env, &Questions::Answer(election_id, q, v.clone()));
+            }
+        }
 
-        for c in candidates {
-            let key = Candidates::Candidate(c);
-            env.storage().persistent().remove(&key);
+        let end = Self::turnout(&env, election_id).min(start.saturating_add(limit));
+        for i in start..end {
+            Self::extend_if_present(&env, &Ballots::Participant(election_id, i));
         }
 
-        env.storage().persistent().remove(&REGISTERED_VOTERS);
-        env.storage().persistent().remove(&REGISTERED_CANDIDATES);
-        env.storage().persistent().remove(&APPROVED_VOTERS);
-        env.storage().persistent().remove(&APPROVED_CANDIDATES);
-        env.storage().persistent().remove(&VOTED_VOTERS);
-        env.storage().persistent().set(&VOTER_ID_COUNTER, &1);
-        env.storage().persistent().set(&CANDIDATE_ID_COUNTER, &1);
-        env.storage().persistent().set(&START_TIME, &0);
-        env.storage().persistent().set(&END_TIME, &0);
+        // Every chain entry, answer sets and superseded ballots included, so
+        // any receipt ever issued keeps verifying.
+        let (_, chain_len) = Self::ballot_root(&env, election_id);
+        let end = chain_len.min(start.saturating_add(limit));
+        for i in start..end {
+            Self::extend_if_present(&env, &Ballots::Chain(election_id, i + 1));
+        }
     }
 
-    pub fn vote(env: Env, candidate_address: Address, voter_address: Address) {
+    pub fn vote(env: Env, candidate_address: Address, voter_address: Address) -> Receipt {
         Self::only_during_voting_period(&env);
-        let mut voter = env
-            .storage()
-            .persistent()
-            .get(&Voters::Voter(voter_address.clone()))
-            .unwrap_or(Voter {
-                has_voted: false,
-                ipfs: String::from_str(&env, "NotFound"),
-                message: String::from_str(&env, ""),
-                name: String::from_str(&env, ""),
-                voter_address: voter_address.clone(),
-                register_id: U256::from_u32(&env, 0),
-                status: REJECTED,
-            });
-
-        assert_ne!(
-            voter.ipfs,
-            String::from_str(&env, "NotFound"),
-            "Account Not Found"
-        );
-        assert_eq!(voter.status, APPROVED, "You are not an approved voter.");
-        assert!(!voter.has_voted, "You have already voted.");
+        let election = Self::current_election(&env);
+        let voter = Self::ballot_voter(&env, election, voter_address);
 
-        let mut candidate = env
-            .storage()
-            .persistent()
-            .get(&Candidates::Candidate(candidate_address.clone()))
-            .unwrap_or(Candidate {
-                ipfs: String::from_str(&env, "NotFound"),
-                message: String::from_str(&env, ""),
-                name: String::from_str(&env, ""),
-                candidate_address: candidate_address.clone(),
-                register_id: U256::from_u32(&env, 0),
-                status: REJECTED,
-                vote_count: U256::from_u32(&env, 0),
-            });
+        let mut candidate = Self::candidate_of(&env, election, candidate_address.clone());
         assert_ne!(
             candidate.ipfs,
             String::from_str(&env, "NotFound"),
@@ -568,79 +2316,347 @@ impl VotingOrganization {
         );
         assert_eq!(candidate.status, APPROVED, "Candidate is not approved.");
 
-        voter.has_voted = true;
-        candidate.vote_count = candidate.vote_count.add(&U256::from_u32(&env, 1));
+        candidate.vote_count = Self::ensure(
+            &env,
+            candidate.vote_count.checked_add(1).ok_or(Error::Overflow),
+        );
+
+        env.storage().persistent().set(
+            &Candidates::Candidate(election, candidate_address.clone()),
+            &candidate,
+        );
+        Self::cast(&env, election, voter, Ballot::Candidate(candidate_address))
+    }
+
+    /// Same as `vote`, naming the candidate by `register_id`.
+    pub fn vote_for_id(env: Env, candidate_id: u32, voter_address: Address) -> Receipt {
+        let election = Self::current_election(&env);
+        let candidate_address = Self::candidate_address(&env, election, candidate_id);
+        Self::vote(env, candidate_address, voter_address)
+    }
+
+    /// Approves every candidate in `candidates`, each adding one to their
+    /// `vote_count`. Only for `Approval` elections.
+    pub fn vote_approval(env: Env, candidates: Vec<Address>, voter_address: Address) -> Receipt {
+        Self::only_during_voting_period(&env);
+        let election = Self::current_election(&env);
+        if Self::voting_method(&env, election) != VotingMethod::Approval {
+            panic_with_error!(&env, Error::WrongVotingMethod);
+        }
+        let voter = Self::ballot_voter(&env, election, voter_address);
+
+        Self::check_choices(&env, election, &candidates);
+        for address in candidates.iter() {
+            Self::add_vote(&env, election, address);
+        }
+
+        Self::cast(&env, election, voter, Ballot::Approval(candidates))
+    }
+
+    /// Ranks candidates in order of preference; voters need not rank them
+    /// all. Only for `Ranked` elections. A candidate's `vote_count` is their
+    /// first-preference count; the seats are decided by `get_winners`.
+    pub fn vote_ranked(env: Env, ranking: Vec<Address>, voter_address: Address) -> Receipt {
+        Self::only_during_voting_period(&env);
+        let election = Self::current_election(&env);
+        if Self::voting_method(&env, election) != VotingMethod::Ranked {
+            panic_with_error!(&env, Error::WrongVotingMethod);
+        }
+        let voter = Self::ballot_voter(&env, election, voter_address);
+
+        Self::check_choices(&env, election, &ranking);
+        Self::add_vote(&env, election, ranking.get_unchecked(0));
+
+        Self::cast(&env, election, voter, Ballot::Ranked(ranking))
+    }
+
+    /// Casts a ballot for no candidate. The voter counts towards turnout
+    /// and `abstentions` but adds to no candidate's `vote_count`.
+    pub fn abstain(env: Env, voter_address: Address) -> Receipt {
+        Self::only_during_voting_period(&env);
+        let election = Self::current_election(&env);
+        let voter = Self::ballot_voter(&env, election, voter_address);
+
+        Self::increment(&env, &(ABSTENTIONS, election));
 
+        Self::cast(&env, election, voter, Ballot::Abstain)
+    }
+
+    /// The receipt for a voter's current ballot in the current election.
+    pub fn get_receipt(env: Env, voter: Address) -> Option<Receipt> {
+        let election = Self::current_election(&env);
         env.storage()
             .persistent()
-            .set(&Candidates::Candidate(candidate_address), &candidate);
+            .get(&Ballots::Receipt(election, voter))
+    }
+
+    /// The latest receipt issued in the current election; its hash commits
+    /// to every ballot cast so far.
+    pub fn get_last_receipt(env: Env) -> Option<Receipt> {
+        Self::last_receipt(&env, Self::current_election(&env))
+    }
+
+    /// Whether `receipt` was issued by this contract, in the current or an
+    /// archived election.
+    pub fn verify_receipt(env: Env, receipt: Receipt) -> bool {
         env.storage()
             .persistent()
-            .set(&Voters::Voter(voter_address.clone()), &voter);
+            .get::<_, BytesN<32>>(&Ballots::Chain(receipt.election_id, receipt.sequence))
+            == Some(receipt.hash)
+    }
 
-        let mut voters_who_voted: Vec<Address> = env
-            .storage()
+    /// Live tallies of the approved candidates together with abstentions
+    /// and turnout.
+    pub fn get_results(env: Env) -> Results {
+        Self::results(&env, Self::current_election(&env))
+    }
+
+    pub fn get_archived_results(env: Env, election_id: u32) -> Results {
+        Self::archived_only(&env, election_id);
+        Self::results(&env, election_id)
+    }
+
+    /// Puts a question on the current election's ballot and returns its
+    /// index. Questions can only be added before voting starts.
+    pub fn add_question(env: Env, text: String, options: Vec<String>, address: Address) -> u32 {
+        Self::owner_only(&env, address);
+
+        let election = Self::current_election(&env);
+        Self::before_voting(&env, election);
+        Self::ensure(&env, validation::question(&text, &options));
+
+        let mut questions = Self::questions(&env, election);
+        if questions.len() >= MAX_QUESTIONS {
+            panic_with_error!(&env, Error::InvalidQuestion);
+        }
+
+        let id = questions.len();
+        let mut tally: Vec<u64> = vec![&env];
+        for _ in 0..options.len() {
+            tally.push_back(0);
+        }
+        questions.push_back(Question { text, options });
+
+        env.storage()
             .persistent()
-            .get(&VOTED_VOTERS)
-            .unwrap_or(vec![&env]);
+            .set(&(QUESTIONS, election), &questions);
+        env.storage()
+            .persistent()
+            .set(&Questions::Tally(election, id), &tally);
 
-        voters_who_voted.push_back(voter_address);
+        id
     }
 
-    pub fn get_all_voters_who_voted(env: Env) -> Vec<Voter> {
-        return env
-            .storage()
+    pub fn get_questions(env: Env) -> Vec<Question> {
+        Self::questions(&env, Self::current_election(&env))
+    }
+
+    /// Answers referendum questions as `(question, option)` pairs. Any
+    /// approved voter may answer, whether or not they voted for a
+    /// candidate, and each question can be answered once (or changed, when
+    /// revoting is allowed); questions left out can be answered in a later
+    /// call while voting is open. Each call is appended to the receipt
+    /// chain like a ballot, and its receipt is returned.
+    pub fn vote_questions(env: Env, answers: Vec<(u32, u32)>, voter_address: Address) -> Receipt {
+        Self::only_during_voting_period(&env);
+        voter_address.require_auth();
+        let election = Self::current_election(&env);
+        let revote = Self::revote_allowed(&env, election);
+
+        let voter = Self::existing_voter(&env, election, voter_address.clone());
+        Self::require_status(&env, &voter.status, APPROVED);
+        if answers.is_empty() {
+            panic_with_error!(&env, Error::InvalidBallot);
+        }
+
+        for (question, option) in answers.iter() {
+            let answer_key = Questions::Answer(election, question, voter_address.clone());
+            let mut tally = Self::question_tally(&env, election, question);
+
+            if let Some(previous) = env.storage().persistent().get::<_, u32>(&answer_key) {
+                if !revote {
+                    panic_with_error!(&env, Error::AlreadyAnswered);
+                }
+                let count = tally.get_unchecked(previous).checked_sub(1);
+                tally.set(previous, Self::ensure(&env, count.ok_or(Error::Overflow)));
+            }
+
+            let count = tally
+                .get(option)
+                .unwrap_or_else(|| panic_with_error!(&env, Error::InvalidBallot));
+            tally.set(
+                option,
+                Self::ensure(&env, count.checked_add(1).ok_or(Error::Overflow)),
+            );
+
+            env.storage()
+                .persistent()
+                .set(&Questions::Tally(election, question), &tally);
+            env.storage().persistent().set(&answer_key, &option);
+        }
+        Self::record_participation(&env, election, voter_address.clone());
+
+        let receipt = Self::append(&env, election, |previous, sequence| {
+            receipt::link_answers(&env, previous, sequence, &voter_address, &answers)
+        });
+        env.events().publish(
+            (symbol_short!("answers"), election),
+            (
+                receipt.sequence,
+                voter_address,
+                answers,
+                receipt.hash.clone(),
+            ),
+        );
+        receipt
+    }
+
+    /// Answers per option of `question`, in the order of its `options`.
+    pub fn get_question_tally(env: Env, question: u32) -> Vec<u64> {
+        Self::question_tally(&env, Self::current_election(&env), question)
+    }
+
+    pub fn get_answer(env: Env, question: u32, voter: Address) -> Option<u32> {
+        let election = Self::current_election(&env);
+        env.storage()
             .persistent()
-            .get(&VOTED_VOTERS)
-            .unwrap_or(vec![&env]);
+            .get(&Questions::Answer(election, question, voter))
+    }
+
+    pub fn get_archived_questions(env: Env, election_id: u32) -> Vec<Question> {
+        Self::archived_only(&env, election_id);
+        Self::questions(&env, election_id)
+    }
+
+    pub fn get_archived_question_tally(env: Env, election_id: u32, question: u32) -> Vec<u64> {
+        Self::archived_only(&env, election_id);
+        Self::question_tally(&env, election_id, question)
+    }
+
+    /// Page of the participation ledger in the order voters first voted or
+    /// answered, at most `MAX_PAGE_SIZE` entries from `start`. A voter
+    /// appears once however many times they revote.
+    pub fn get_all_voters_who_voted(env: Env, start: u32, limit: u32) -> Vec<Participation> {
+        Self::participation(&env, Self::current_election(&env), start, limit)
+    }
+
+    pub fn get_voted_count(env: Env) -> u32 {
+        Self::turnout(&env, Self::current_election(&env))
+    }
+
+    pub fn get_archived_voters_who_voted(
+        env: Env,
+        election_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Participation> {
+        Self::archived_only(&env, election_id);
+        Self::participation(&env, election_id, start, limit)
     }
 
+    /// Leading candidate among those still approved; rejected and withdrawn
+    /// candidates are left out of the result.
     pub fn get_current_voting_status(env: Env) -> Candidate {
+        let election = Self::current_election(&env);
         let mut winning_candidate = Candidate {
             name: String::from_str(&env, ""),
             ipfs: String::from_str(&env, "NotFound"),
+            profile: Profile::empty(&env),
             message: String::from_str(&env, ""),
-            candidate_address: Address::from_string(&String::from_str(&env, "")),
-            register_id: U256::from_u32(&env, 0),
-            vote_count: U256::from_u32(&env, 0),
+            candidate_address: env.current_contract_address(),
+            register_id: 0,
+            vote_count: 0,
             status: REJECTED,
         };
 
-        let candidates: Vec<Address> = env
-            .storage()
-            .persistent()
-            .get(&REGISTERED_CANDIDATES)
-            .unwrap_or(vec![&env]);
-
-        for c in candidates {
-            let key = Candidates::Candidate(c);
-            let cand = env.storage().persistent().get(&key).unwrap_or(Candidate {
-                name: String::from_str(&env, ""),
-                ipfs: String::from_str(&env, ""),
-                message: String::from_str(&env, ""),
-                candidate_address: Address::from_string(&String::from_str(&env, "")),
-                register_id: U256::from_u32(&env, 0),
-                vote_count: U256::from_u32(&env, 0),
-                status: REJECTED,
-            });
-
+        for cand in Self::candidates_of(&env, election, APPROVED_CANDIDATES) {
             if winning_candidate.vote_count < cand.vote_count {
                 winning_candidate = cand;
             }
         }
 
-        return winning_candidate;
+        winning_candidate
     }
 
     pub fn get_winning_candidate(env: Env) -> Candidate {
-        let end_time: u64 = env.storage().persistent().get(&END_TIME).unwrap_or(0);
+        let end_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&(END_TIME, Self::current_election(&env)))
+            .unwrap_or(0);
         assert!(env.ledger().timestamp() > end_time);
-        return Self::get_current_voting_status(env);
+        Self::get_current_voting_status(env)
+    }
+
+    /// The candidates filling the election's seats once voting has ended,
+    /// in order of votes (or, for `Ranked`, in the order STV elected them).
+    /// Fewer than `get_seats` are returned if fewer candidates are approved.
+    ///
+    /// `Ranked` elections read every ballot, so for large rolls this is
+    /// meant to be simulated rather than submitted.
+    pub fn get_winners(env: Env) -> Vec<Candidate> {
+        let election = Self::current_election(&env);
+        let end_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&(END_TIME, election))
+            .unwrap_or(0);
+        if env.ledger().timestamp() <= end_time {
+            panic_with_error!(&env, Error::VotingNotEnded);
+        }
+
+        let seats = Self::seats(&env, election);
+        if Self::voting_method(&env, election) != VotingMethod::Ranked {
+            let candidates = Self::candidates_of(&env, election, APPROVED_CANDIDATES);
+            return tally::top(&env, candidates, seats);
+        }
+
+        let candidates: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(APPROVED_CANDIDATES, election))
+            .unwrap_or(vec![&env]);
+        let voters: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(REGISTERED_VOTERS, election))
+            .unwrap_or(vec![&env]);
+
+        let mut ballots: Vec<Vec<Address>> = vec![&env];
+        for voter in voters {
+            match env
+                .storage()
+                .persistent()
+                .get(&Ballots::Ballot(election, voter))
+            {
+                Some(Ballot::Candidate(c)) => ballots.push_back(vec![&env, c]),
+                Some(Ballot::Ranked(ranking)) => ballots.push_back(ranking),
+                _ => {}
+            }
+        }
+
+        let mut winners = vec![&env];
+        for address in tally::stv(&env, candidates, &ballots, seats) {
+            winners.push_back(Self::candidate_of(&env, election, address));
+        }
+        winners
     }
 
     pub fn get_voting_time(env: Env) -> Vec<u64> {
-        let start_time: u64 = env.storage().persistent().get(&START_TIME).unwrap_or(0);
-        let end_time: u64 = env.storage().persistent().get(&END_TIME).unwrap_or(0);
-        return vec![&env, start_time, end_time];
+        let election = Self::current_election(&env);
+        let start_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&(START_TIME, election))
+            .unwrap_or(0);
+        let end_time: u64 = env
+            .storage()
+            .persistent()
+            .get(&(END_TIME, election))
+            .unwrap_or(0);
+        vec![&env, start_time, end_time]
     }
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,62 @@
+//! Hash chain over the ballots of an election.
+//!
+//! Ballot `n` (counting from 1) gets
+//! `sha256(hash(n - 1) || n as 4 big-endian bytes || xdr(voter) || xdr(ballot))`,
+//! with `hash(0)` being 32 zero bytes. A receipt is `(n, hash(n))`, so a
+//! voter holding one can check it against the contract, and anyone
+//! replaying the ballots in order reaches the same latest hash.
+//!
+//! Referendum answers take a place in the same sequence, hashed the same way
+//! with `xdr(answers)`, the `(question, option)` pairs of the call, in place
+//! of `xdr(ballot)`. Both encode as XDR vectors, but a ballot's starts with
+//! its variant name and an answer set's with a pair, so neither can be read
+//! as the other.
+//!
+//! Every ballot is published as a `("ballot", election_id)` event with data
+//! `(n, voter, ballot, hash(n))`, and every answer set as an
+//! `("answers", election_id)` event with data `(n, voter, answers, hash(n))`,
+//! which is all an auditor needs to rebuild the chain and compare it with
+//! `get_ballot_root`. Revotes are appended like any other ballot; only a
+//! voter's latest ballot and answers are counted.
+
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+use crate::Ballot;
+
+pub(crate) fn genesis(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[0; 32])
+}
+
+pub(crate) fn link(
+    env: &Env,
+    previous: &BytesN<32>,
+    sequence: u32,
+    voter: &Address,
+    ballot: &Ballot,
+) -> BytesN<32> {
+    chain(env, previous, sequence, voter, ballot.clone().to_xdr(env))
+}
+
+pub(crate) fn link_answers(
+    env: &Env,
+    previous: &BytesN<32>,
+    sequence: u32,
+    voter: &Address,
+    answers: &Vec<(u32, u32)>,
+) -> BytesN<32> {
+    chain(env, previous, sequence, voter, answers.clone().to_xdr(env))
+}
+
+fn chain(
+    env: &Env,
+    previous: &BytesN<32>,
+    sequence: u32,
+    voter: &Address,
+    entry: Bytes,
+) -> BytesN<32> {
+    let mut data: Bytes = previous.clone().into();
+    data.extend_from_array(&sequence.to_be_bytes());
+    data.append(&voter.clone().to_xdr(env));
+    data.append(&entry);
+    env.crypto().sha256(&data).into()
+}
@@ -0,0 +1,140 @@
+//! Seat allocation for `get_winners`.
+//!
+//! Ties always go to the candidate listed first in
+//! `get_all_approved_candidates`, for electing as well as for eliminating
+//! (the later-listed candidate is eliminated first).
+
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::Candidate;
+
+// Fixed-point scale for ballot weights during STV surplus transfers.
+const WEIGHT_ONE: u128 = 1_000_000_000;
+
+// Marks a ballot with no preference left among continuing candidates.
+const EXHAUSTED: u32 = u32::MAX;
+
+/// The `seats` candidates with the most votes, most votes first.
+pub(crate) fn top(env: &Env, mut candidates: Vec<Candidate>, seats: u32) -> Vec<Candidate> {
+    let mut winners = Vec::new(env);
+    while winners.len() < seats && !candidates.is_empty() {
+        let mut best = 0;
+        for (i, c) in candidates.iter().enumerate() {
+            if c.vote_count > candidates.get_unchecked(best).vote_count {
+                best = i as u32;
+            }
+        }
+        winners.push_back(candidates.get_unchecked(best));
+        candidates.remove(best);
+    }
+    winners
+}
+
+/// Single Transferable Vote with a Droop quota. When a candidate reaches
+/// the quota, every ballot counting for them moves on to its next
+/// continuing preference at a weight scaled by `surplus / tally` (Gregory
+/// method); when nobody does, the candidate with the lowest tally is
+/// eliminated and their ballots move on at full weight. Elected candidates
+/// are returned in the order they were elected.
+pub(crate) fn stv(
+    env: &Env,
+    mut continuing: Vec<Address>,
+    ballots: &Vec<Vec<Address>>,
+    seats: u32,
+) -> Vec<Address> {
+    let mut elected = Vec::new(env);
+    let mut weights = Vec::new(env);
+    let mut valid: u128 = 0;
+    for ballot in ballots.iter() {
+        if ballot.iter().any(|c| continuing.contains(&c)) {
+            valid += 1;
+        }
+        weights.push_back(WEIGHT_ONE);
+    }
+    let quota = (valid / (seats as u128 + 1) + 1) * WEIGHT_ONE;
+
+    while elected.len() < seats && !continuing.is_empty() {
+        let (tallies, tops) = count(env, &continuing, ballots, &weights);
+
+        if elected.len() + continuing.len() <= seats {
+            // Everyone left gets a seat; order them by current tally.
+            let mut order: Vec<u32> = Vec::new(env);
+            for i in 0..continuing.len() {
+                order.push_back(i);
+            }
+            while let Some(best) = best_of(&tallies, &order) {
+                elected.push_back(continuing.get_unchecked(order.get_unchecked(best)));
+                order.remove(best);
+            }
+            break;
+        }
+
+        let mut best = 0;
+        let mut worst = 0;
+        for (i, tally) in tallies.iter().enumerate() {
+            if tally > tallies.get_unchecked(best) {
+                best = i as u32;
+            }
+            if tally <= tallies.get_unchecked(worst) {
+                worst = i as u32;
+            }
+        }
+
+        let best_tally = tallies.get_unchecked(best);
+        if best_tally >= quota {
+            let surplus = best_tally - quota;
+            for (i, top) in tops.iter().enumerate() {
+                if top == best {
+                    let i = i as u32;
+                    weights.set(i, weights.get_unchecked(i) * surplus / best_tally);
+                }
+            }
+            elected.push_back(continuing.get_unchecked(best));
+            continuing.remove(best);
+        } else {
+            continuing.remove(worst);
+        }
+    }
+
+    elected
+}
+
+/// Each continuing candidate's tally, and which candidate (by index in
+/// `continuing`) each ballot currently counts for.
+fn count(
+    env: &Env,
+    continuing: &Vec<Address>,
+    ballots: &Vec<Vec<Address>>,
+    weights: &Vec<u128>,
+) -> (Vec<u128>, Vec<u32>) {
+    let mut tallies = Vec::new(env);
+    for _ in 0..continuing.len() {
+        tallies.push_back(0u128);
+    }
+
+    let mut tops = Vec::new(env);
+    for (ballot, weight) in ballots.iter().zip(weights.iter()) {
+        let top = ballot
+            .iter()
+            .find_map(|c| continuing.first_index_of(&c))
+            .unwrap_or(EXHAUSTED);
+        if top != EXHAUSTED {
+            tallies.set(top, tallies.get_unchecked(top) + weight);
+        }
+        tops.push_back(top);
+    }
+
+    (tallies, tops)
+}
+
+/// Position in `order` of the index with the highest tally.
+fn best_of(tallies: &Vec<u128>, order: &Vec<u32>) -> Option<u32> {
+    let mut best: Option<(u32, u128)> = None;
+    for (pos, i) in order.iter().enumerate() {
+        let tally = tallies.get_unchecked(i);
+        if best.is_none_or(|(_, t)| tally > t) {
+            best = Some((pos as u32, tally));
+        }
+    }
+    best.map(|(pos, _)| pos)
+}
@@ -0,0 +1,1932 @@
+extern crate std;
+
+use ed25519_dalek::{Signer, SigningKey};
+use proptest::prelude::*;
+use soroban_sdk::testutils::storage::{Instance as _, Persistent as _};
+use soroban_sdk::testutils::{Address as _, EnvTestConfig, Events, Ledger};
+use soroban_sdk::{Bytes, ConversionError, InvokeError};
+
+use super::*;
+
+mod scenarios;
+
+const CID: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+const OTHER_CID: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+const START: u64 = 1_000;
+const END: u64 = 2_000;
+
+type TryResult<T> = Result<Result<T, ConversionError>, Result<soroban_sdk::Error, InvokeError>>;
+
+/// Asserts that a call was rejected with `error`.
+fn fails_with<T>(result: TryResult<T>, error: Error) {
+    match result {
+        Err(Ok(e)) => assert_eq!(e, error.into()),
+        Err(Err(e)) => panic!("expected {:?}, got {:?}", error, e),
+        Ok(_) => panic!("expected {:?}, but the call succeeded", error),
+    }
+}
+
+/// Asserts that a call was rejected, for checks that panic with a message
+/// or fail in the host rather than returning an `Error`.
+fn fails<T>(result: TryResult<T>) {
+    assert!(result.is_err(), "expected the call to fail");
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: VotingOrganizationClient<'a>,
+    owner: Address,
+}
+
+impl Setup<'_> {
+    fn new() -> Self {
+        Self::with_env(Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        }))
+    }
+
+    /// Without the resource budget, for property tests whose random call
+    /// sequences can run longer than a single transaction would.
+    fn unmetered() -> Self {
+        let s = Self::new();
+        s.env.budget().reset_unlimited();
+        s
+    }
+
+    /// With the sdk's test snapshot written when the environment is dropped,
+    /// for the scenarios whose final state is committed.
+    fn recorded() -> Self {
+        Self::with_env(Env::default())
+    }
+
+    fn with_env(env: Env) -> Self {
+        env.mock_all_auths();
+        let id = env.register_contract(None, VotingOrganization);
+        let client = VotingOrganizationClient::new(&env, &id);
+        let owner = Address::generate(&env);
+        client.init(&owner);
+        Setup { env, client, owner }
+    }
+
+    fn str(&self, value: &str) -> String {
+        String::from_str(&self.env, value)
+    }
+
+    fn registered_voter(&self) -> Address {
+        let address = Address::generate(&self.env);
+        self.client
+            .registerVoter(&self.str("Voter"), &self.str(CID), &address);
+        address
+    }
+
+    fn voter(&self) -> Address {
+        let address = self.registered_voter();
+        self.client.approve_voter(&address, &self.str("ok"));
+        address
+    }
+
+    fn registered_candidate(&self) -> Address {
+        let address = Address::generate(&self.env);
+        self.client
+            .register_candidate(&self.str("Candidate"), &self.str(CID), &address);
+        address
+    }
+
+    fn candidate(&self) -> Address {
+        let address = self.registered_candidate();
+        self.client.approve_candidate(&address, &self.str("ok"));
+        address
+    }
+
+    fn schedule(&self) {
+        self.client.set_voting_period(&START, &END, &self.owner);
+    }
+
+    fn open(&self) {
+        self.schedule();
+        self.env.ledger().set_timestamp(START);
+    }
+
+    fn close(&self) {
+        self.env.ledger().set_timestamp(END + 1);
+    }
+
+    /// Makes every `require_auth` fail from here on.
+    fn deny_auths(&self) {
+        self.env.set_auths(&[]);
+    }
+
+    fn profile(&self) -> Profile {
+        Profile {
+            affiliation: self.str("Independent"),
+            position: self.str("Treasurer"),
+            manifesto: self.str(OTHER_CID),
+            image: self.str(""),
+            contact: self.str("@treasurer"),
+            metadata: Map::new(&self.env),
+        }
+    }
+}
+
+// Setup, ownership and upgrades.
+
+#[test]
+fn init_opens_the_first_election() {
+    let s = Setup::new();
+
+    assert_eq!(s.client.get_current_election(), 1);
+    assert_eq!(s.client.get_version(), CONTRACT_VERSION);
+    assert_eq!(s.client.get_voting_time(), vec![&s.env, 0, 0]);
+    assert_eq!(s.client.get_seats(), 1);
+    assert_eq!(s.client.get_voting_method(), VotingMethod::Plurality);
+    assert_eq!(
+        s.client.get_withdrawal_policy(),
+        WithdrawalPolicy::VoidVotes
+    );
+    assert!(!s.client.get_allow_revote());
+}
+
+#[test]
+fn init_only_runs_once() {
+    let s = Setup::new();
+    let stranger = Address::generate(&s.env);
+    s.registered_voter();
+
+    fails_with(s.client.try_init(&stranger), Error::AlreadyInitialized);
+    fails(s.client.try_set_seats(&2, &stranger));
+    assert_eq!(s.client.get_voter(&s.registered_voter()).register_id, 2);
+}
+
+#[test]
+fn registrar_decisions_need_the_owner_signature() {
+    let s = Setup::new();
+    let voter = s.registered_voter();
+
+    s.client.approve_voter(&voter, &s.str("ok"));
+    assert_eq!(s.env.auths()[0].0, s.owner);
+
+    let other = s.registered_voter();
+    s.deny_auths();
+    fails(s.client.try_approve_voter(&other, &s.str("ok")));
+}
+
+#[test]
+fn change_owner_hands_over_owner_calls() {
+    let s = Setup::new();
+    let new_owner = Address::generate(&s.env);
+
+    fails(s.client.try_change_owner(&new_owner, &new_owner));
+    s.client.change_owner(&new_owner, &s.owner);
+
+    fails(s.client.try_set_seats(&2, &s.owner));
+    s.client.set_seats(&2, &new_owner);
+    assert_eq!(s.client.get_seats(), 2);
+}
+
+#[test]
+fn upgrade_is_owner_only_and_blocked_while_voting() {
+    let s = Setup::new();
+    let hash = BytesN::from_array(&s.env, &[1; 32]);
+    let stranger = Address::generate(&s.env);
+
+    fails(s.client.try_upgrade(&hash, &stranger));
+
+    s.open();
+    fails_with(
+        s.client.try_upgrade(&hash, &s.owner),
+        Error::VotingInProgress,
+    );
+}
+
+#[test]
+fn migrate_is_owner_only_and_needs_an_older_version() {
+    let s = Setup::new();
+
+    fails(s.client.try_migrate(&Address::generate(&s.env)));
+    fails_with(s.client.try_migrate(&s.owner), Error::AlreadyMigrated);
+    assert_eq!(s.client.get_version(), CONTRACT_VERSION);
+}
+
+#[test]
+fn voters_are_numbered_from_one_and_start_pending() {
+    let s = Setup::new();
+    let first = s.registered_voter();
+    let second = s.registered_voter();
+
+    let voter = s.client.get_voter(&first);
+    assert_eq!(voter.register_id, 1);
+    assert_eq!(voter.status, PENDING);
+    assert!(!voter.has_voted);
+    assert_eq!(s.client.get_voter_by_id(&2).voter_address, second);
+    assert_eq!(s.client.get_all_registered_voters().len(), 2);
+    assert!(s.client.get_all_approved_voters().is_empty());
+}
+
+#[test]
+fn candidates_are_numbered_separately_from_voters() {
+    let s = Setup::new();
+    s.registered_voter();
+    s.registered_voter();
+    let candidate = s.registered_candidate();
+
+    assert_eq!(s.client.get_candidate(&candidate).register_id, 1);
+    assert_eq!(
+        s.client.get_candidate_by_id(&1).candidate_address,
+        candidate
+    );
+    assert_eq!(s.client.get_candidate(&candidate).status, PENDING);
+}
+
+#[test]
+fn registering_twice_fails() {
+    let s = Setup::new();
+    let voter = s.registered_voter();
+    let candidate = s.registered_candidate();
+
+    fails_with(
+        s.client
+            .try_registerVoter(&s.str("Again"), &s.str(CID), &voter),
+        Error::AlreadyRegistered,
+    );
+    fails_with(
+        s.client
+            .try_register_candidate(&s.str("Again"), &s.str(CID), &candidate),
+        Error::AlreadyRegistered,
+    );
+}
+
+#[test]
+fn registration_needs_the_registrants_signature() {
+    let s = Setup::new();
+    let voter = s.registered_voter();
+    assert_eq!(s.env.auths()[0].0, voter);
+    let candidate = s.registered_candidate();
+    assert_eq!(s.env.auths()[0].0, candidate);
+
+    s.deny_auths();
+    let (voter, candidate) = (Address::generate(&s.env), Address::generate(&s.env));
+    fails(
+        s.client
+            .try_registerVoter(&s.str("Voter"), &s.str(CID), &voter),
+    );
+    fails(
+        s.client
+            .try_register_candidate(&s.str("Candidate"), &s.str(CID), &candidate),
+    );
+    assert_eq!(s.client.get_all_registered_voters().len(), 1);
+    assert_eq!(s.client.get_all_registered_candidates().len(), 1);
+}
+
+#[test]
+fn registration_validates_name_and_cid() {
+    let s = Setup::new();
+    let address = Address::generate(&s.env);
+
+    fails_with(
+        s.client
+            .try_registerVoter(&s.str(""), &s.str(CID), &address),
+        Error::InvalidName,
+    );
+    fails_with(
+        s.client.try_registerVoter(
+            &s.str("Voter"),
+            &s.str(
+                "https://gateway.pinata.cloud/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            ),
+            &address,
+        ),
+        Error::InvalidCid,
+    );
+    fails_with(
+        s.client
+            .try_register_candidate(&s.str("Candidate"), &s.str("NotFound"), &address),
+        Error::InvalidCid,
+    );
+    s.client
+        .registerVoter(&s.str("Voter"), &s.str(OTHER_CID), &address);
+}
+
+#[test]
+fn unknown_records() {
+    let s = Setup::new();
+    let stranger = Address::generate(&s.env);
+
+    fails_with(s.client.try_get_voter_by_id(&1), Error::NotFound);
+    fails_with(s.client.try_get_candidate_by_id(&1), Error::NotFound);
+    assert_eq!(s.client.get_voter(&stranger).ipfs, s.str("NotFound"));
+    assert_eq!(s.client.get_candidate(&stranger).ipfs, s.str("NotFound"));
+}
+
+#[test]
+fn allowlisted_voters_register_approved() {
+    let s = Setup::new();
+    let member = Address::generate(&s.env);
+    let stranger = Address::generate(&s.env);
+
+    fails(
+        s.client
+            .try_set_allowlist(&vec![&s.env, member.clone()], &true, &stranger),
+    );
+    s.client
+        .set_allowlist(&vec![&s.env, member.clone()], &true, &s.owner);
+    assert!(s.client.is_allowlisted(&member));
+
+    s.client
+        .register_allowlisted(&s.str("Member"), &s.str(CID), &member);
+    assert_eq!(s.client.get_voter(&member).status, APPROVED);
+    assert_eq!(s.client.get_all_approved_voters().len(), 1);
+
+    fails_with(
+        s.client
+            .try_register_allowlisted(&s.str("Stranger"), &s.str(CID), &stranger),
+        Error::NotEligible,
+    );
+
+    s.client
+        .set_allowlist(&vec![&s.env, member.clone()], &false, &s.owner);
+    assert!(!s.client.is_allowlisted(&member));
+}
+
+#[test]
+fn queued_voters_who_prove_eligibility_are_approved_in_place() {
+    let s = Setup::new();
+    s.registered_voter();
+    let queued = s.registered_voter();
+    let rejected = s.registered_voter();
+    s.client.reject_voter(&rejected, &s.str("Blurry photo"));
+    s.client.set_allowlist(
+        &vec![&s.env, queued.clone(), rejected.clone()],
+        &true,
+        &s.owner,
+    );
+
+    s.client
+        .register_allowlisted(&s.str("Member"), &s.str(OTHER_CID), &queued);
+    let voter = s.client.get_voter(&queued);
+    assert_eq!(voter.status, APPROVED);
+    assert_eq!(voter.register_id, 2);
+    assert_eq!(voter.ipfs, s.str(OTHER_CID));
+    assert_eq!(s.client.get_voter_history(&queued).len(), 1);
+    assert_eq!(s.client.get_all_registered_voters().len(), 3);
+    assert_eq!(s.client.get_all_approved_voters(), vec![&s.env, voter]);
+
+    // Only the pending queue is skipped; other records stay as they are.
+    fails_with(
+        s.client
+            .try_register_allowlisted(&s.str("Member"), &s.str(CID), &queued),
+        Error::AlreadyRegistered,
+    );
+    fails_with(
+        s.client
+            .try_register_allowlisted(&s.str("Member"), &s.str(CID), &rejected),
+        Error::AlreadyRegistered,
+    );
+}
+
+#[test]
+fn eligibility_proofs_register_approved() {
+    let s = Setup::new();
+    let member = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    let stranger = Address::generate(&s.env);
+
+    fails_with(
+        s.client
+            .try_register_with_proof(&s.str("Member"), &s.str(CID), &member, &vec![&s.env]),
+        Error::NotEligible,
+    );
+
+    let (leaf, sibling) = s.env.as_contract(&s.client.address, || {
+        (
+            eligibility::leaf(&s.env, &member),
+            eligibility::leaf(&s.env, &other),
+        )
+    });
+    let leaf_of_member = leaf.clone();
+    let mut pair = Bytes::new(&s.env);
+    let (low, high) = if leaf < sibling {
+        (leaf, sibling.clone())
+    } else {
+        (sibling.clone(), leaf)
+    };
+    pair.append(&low.into());
+    pair.append(&high.into());
+    let root: BytesN<32> = s.env.crypto().sha256(&pair).into();
+
+    s.client.set_eligibility_root(&root, &s.owner);
+    assert_eq!(s.client.get_eligibility_root(), Some(root));
+
+    s.client.register_with_proof(
+        &s.str("Member"),
+        &s.str(CID),
+        &member,
+        &vec![&s.env, sibling.clone()],
+    );
+    assert_eq!(s.client.get_voter(&member).status, APPROVED);
+
+    // A member already in the manual queue is approved by their proof too.
+    s.client.registerVoter(&s.str("Other"), &s.str(CID), &other);
+    s.client.register_with_proof(
+        &s.str("Other"),
+        &s.str(CID),
+        &other,
+        &vec![&s.env, leaf_of_member],
+    );
+    assert_eq!(s.client.get_voter(&other).status, APPROVED);
+
+    fails_with(
+        s.client.try_register_with_proof(
+            &s.str("Stranger"),
+            &s.str(CID),
+            &stranger,
+            &vec![&s.env, sibling],
+        ),
+        Error::NotEligible,
+    );
+}
+
+#[test]
+fn vouchers_register_approved() {
+    let s = Setup::new();
+    let member = Address::generate(&s.env);
+    let signer = SigningKey::from_bytes(&[7; 32]);
+    let forger = SigningKey::from_bytes(&[9; 32]);
+
+    let message = s.env.as_contract(&s.client.address, || {
+        eligibility::voucher_message(&s.env, 1, &member)
+    });
+    let mut bytes = std::vec![0; message.len() as usize];
+    message.copy_into_slice(&mut bytes);
+    let sign = |key: &SigningKey| BytesN::from_array(&s.env, &key.sign(&bytes).to_bytes());
+
+    fails_with(
+        s.client
+            .try_register_with_voucher(&s.str("Member"), &s.str(CID), &member, &sign(&signer)),
+        Error::NotEligible,
+    );
+
+    let public_key = BytesN::from_array(&s.env, &signer.verifying_key().to_bytes());
+    s.client.set_voucher_signer(&public_key, &s.owner);
+    assert_eq!(s.client.get_voucher_signer(), Some(public_key));
+
+    fails(s.client.try_register_with_voucher(
+        &s.str("Member"),
+        &s.str(CID),
+        &member,
+        &sign(&forger),
+    ));
+    // Registering by hand first only queues the member; the voucher still
+    // approves them.
+    s.client
+        .registerVoter(&s.str("Member"), &s.str(CID), &member);
+    s.client
+        .register_with_voucher(&s.str("Member"), &s.str(CID), &member, &sign(&signer));
+    assert_eq!(s.client.get_voter(&member).status, APPROVED);
+    assert_eq!(s.client.get_voter(&member).register_id, 1);
+}
+
+// Registrar decisions.
+
+#[test]
+fn approving_a_voter() {
+    let s = Setup::new();
+    let voter = s.registered_voter();
+
+    s.client.approve_voter(&voter, &s.str("Welcome"));
+    let record = s.client.get_voter(&voter);
+    assert_eq!(record.status, APPROVED);
+    assert_eq!(record.message, s.str("Welcome"));
+    assert_eq!(s.client.get_all_approved_voters(), vec![&s.env, record]);
+
+    fails_with(
+        s.client.try_approve_voter(&voter, &s.str("again")),
+        Error::InvalidStatus,
+    );
+    fails_with(
+        s.client
+            .try_approve_voter(&Address::generate(&s.env), &s.str("ok")),
+        Error::NotFound,
+    );
+    let long = s.str(&"x".repeat(257));
+    fails_with(
+        s.client.try_approve_voter(&s.registered_voter(), &long),
+        Error::InvalidMessage,
+    );
+}
+
+#[test]
+fn rejected_voters_can_reapply() {
+    let s = Setup::new();
+    let voter = s.registered_voter();
+
+    fails_with(
+        s.client
+            .try_reapply_voter(&s.str("Voter"), &s.str(OTHER_CID), &voter),
+        Error::InvalidStatus,
+    );
+
+    s.client.reject_voter(&voter, &s.str("Blurry photo"));
+    assert_eq!(s.client.get_voter(&voter).status, REJECTED);
+    assert!(s.client.get_all_approved_voters().is_empty());
+
+    s.client
+        .reapply_voter(&s.str("Voter"), &s.str(OTHER_CID), &voter);
+    let record = s.client.get_voter(&voter);
+    assert_eq!(record.status, PENDING);
+    assert_eq!(record.ipfs, s.str(OTHER_CID));
+    assert_eq!(record.register_id, 1);
+    assert_eq!(s.client.get_voter_history(&voter).len(), 1);
+}
+
+#[test]
+fn rejected_candidates_can_reapply() {
+    let s = Setup::new();
+    let candidate = s.registered_candidate();
+
+    s.client
+        .reject_candidate(&candidate, &s.str("Missing forms"));
+    assert_eq!(s.client.get_candidate(&candidate).status, REJECTED);
+
+    s.client
+        .reapply_candidate(&s.str("Candidate"), &s.str(OTHER_CID), &candidate);
+    assert_eq!(s.client.get_candidate(&candidate).status, PENDING);
+
+    s.client.approve_candidate(&candidate, &s.str("ok"));
+    fails_with(
+        s.client
+            .try_reapply_candidate(&s.str("Candidate"), &s.str(CID), &candidate),
+        Error::InvalidStatus,
+    );
+    assert_eq!(s.client.get_all_approved_candidates().len(), 1);
+}
+
+#[test]
+fn batch_decisions_report_each_item() {
+    let s = Setup::new();
+    let (a, b) = (s.registered_voter(), s.registered_voter());
+    let stranger = Address::generate(&s.env);
+    let ok = s.str("ok");
+
+    let outcomes = s.client.approve_voters(&vec![
+        &s.env,
+        (a.clone(), ok.clone()),
+        (stranger.clone(), ok.clone()),
+        (a.clone(), ok.clone()),
+    ]);
+    assert_eq!(
+        outcomes,
+        vec![
+            &s.env,
+            Outcome::Applied,
+            Outcome::Failed(Error::NotFound as u32),
+            Outcome::Failed(Error::InvalidStatus as u32),
+        ]
+    );
+    assert_eq!(
+        s.client
+            .reject_voters(&vec![&s.env, (b.clone(), ok.clone())]),
+        vec![&s.env, Outcome::Applied]
+    );
+    assert_eq!(s.client.get_voter(&b).status, REJECTED);
+
+    let (c, d) = (s.registered_candidate(), s.registered_candidate());
+    s.client
+        .approve_candidates(&vec![&s.env, (c.clone(), ok.clone())]);
+    s.client
+        .reject_candidates(&vec![&s.env, (d.clone(), ok.clone())]);
+    assert_eq!(s.client.get_candidate(&c).status, APPROVED);
+    assert_eq!(s.client.get_candidate(&d).status, REJECTED);
+
+    let mut too_many = vec![&s.env];
+    for _ in 0..=MAX_BATCH_SIZE {
+        too_many.push_back((stranger.clone(), ok.clone()));
+    }
+    fails_with(s.client.try_approve_voters(&too_many), Error::BatchTooLarge);
+    fails_with(
+        s.client.try_reject_candidates(&too_many),
+        Error::BatchTooLarge,
+    );
+}
+
+#[test]
+fn importing_a_roll() {
+    let s = Setup::new();
+    let (a, b) = (Address::generate(&s.env), Address::generate(&s.env));
+    let entry = |address: &Address, ipfs: &str| (address.clone(), s.str("Member"), s.str(ipfs));
+
+    let outcomes = s.client.import_voters(&vec![
+        &s.env,
+        entry(&a, CID),
+        entry(&a, CID),
+        entry(&b, "not-a-cid"),
+        entry(&b, OTHER_CID),
+    ]);
+    assert_eq!(
+        outcomes,
+        vec![
+            &s.env,
+            Outcome::Applied,
+            Outcome::Failed(Error::AlreadyRegistered as u32),
+            Outcome::Failed(Error::InvalidCid as u32),
+            Outcome::Applied,
+        ]
+    );
+    assert_eq!(s.client.get_all_approved_voters().len(), 2);
+    assert_eq!(s.client.get_voter(&b).register_id, 2);
+
+    let mut full = vec![&s.env];
+    for _ in 0..MAX_IMPORT_SIZE {
+        full.push_back(entry(&Address::generate(&s.env), CID));
+    }
+    s.client.import_voters(&full);
+    assert_eq!(
+        s.client.get_all_approved_voters().len(),
+        2 + MAX_IMPORT_SIZE
+    );
+
+    full.push_back(entry(&Address::generate(&s.env), CID));
+    fails_with(s.client.try_import_voters(&full), Error::BatchTooLarge);
+}
+
+#[test]
+fn revoked_voters_cannot_vote() {
+    let s = Setup::new();
+    let voter = s.voter();
+    let candidate = s.candidate();
+
+    fails_with(
+        s.client
+            .try_revoke_voter(&s.registered_voter(), &s.str("no")),
+        Error::InvalidStatus,
+    );
+    s.client.revoke_voter(&voter, &s.str("Moved away"));
+    assert_eq!(s.client.get_voter(&voter).status, REVOKED);
+    assert!(s.client.get_all_approved_voters().is_empty());
+
+    s.open();
+    fails(s.client.try_vote(&candidate, &voter));
+
+    s.client.reapply_voter(&s.str("Voter"), &s.str(CID), &voter);
+    assert_eq!(s.client.get_voter(&voter).status, PENDING);
+}
+
+#[test]
+fn candidates_can_be_withdrawn_until_voting_ends() {
+    let s = Setup::new();
+    let (a, b, c) = (s.candidate(), s.registered_candidate(), s.candidate());
+
+    s.client.withdraw_candidate(&a, &s.str("Disqualified"));
+    assert_eq!(s.client.get_candidate(&a).status, WITHDRAWN);
+    s.client.withdraw_candidacy(&b);
+    assert_eq!(s.client.get_candidate(&b).status, WITHDRAWN);
+    assert_eq!(s.client.get_all_approved_candidates().len(), 1);
+
+    fails_with(
+        s.client.try_withdraw_candidate(&a, &s.str("again")),
+        Error::InvalidStatus,
+    );
+    fails_with(
+        s.client
+            .try_withdraw_candidate(&Address::generate(&s.env), &s.str("no")),
+        Error::NotFound,
+    );
+
+    s.schedule();
+    s.close();
+    fails_with(s.client.try_withdraw_candidacy(&c), Error::VotingEnded);
+}
+
+#[test]
+fn withdrawal_policy_decides_what_happens_to_ballots() {
+    let s = Setup::new();
+    let (a, b) = (s.candidate(), s.candidate());
+    let voter = s.voter();
+
+    s.client
+        .set_withdrawal_policy(&WithdrawalPolicy::ReenableVoters, &s.owner);
+    assert_eq!(
+        s.client.get_withdrawal_policy(),
+        WithdrawalPolicy::ReenableVoters
+    );
+
+    s.open();
+    fails_with(
+        s.client
+            .try_set_withdrawal_policy(&WithdrawalPolicy::VoidVotes, &s.owner),
+        Error::VotingStarted,
+    );
+
+    s.client.vote(&a, &voter);
+    fails(s.client.try_vote(&b, &voter));
+
+    s.client.withdraw_candidacy(&a);
+    s.client.vote(&b, &voter);
+    assert_eq!(s.client.get_candidate(&a).vote_count, 0);
+    assert_eq!(s.client.get_candidate(&b).vote_count, 1);
+    assert_eq!(s.client.get_voted_count(), 1);
+    fails(s.client.try_vote(&b, &voter));
+}
+
+#[test]
+fn voided_votes_stay_with_the_withdrawn_candidate() {
+    let s = Setup::new();
+    let (a, b) = (s.candidate(), s.candidate());
+    let voter = s.voter();
+    s.open();
+
+    s.client.vote(&a, &voter);
+    s.client.withdraw_candidacy(&a);
+    fails(s.client.try_vote(&b, &voter));
+    assert_eq!(s.client.get_candidate(&a).vote_count, 1);
+    assert_eq!(s.client.get_voted_count(), 1);
+}
+
+// Record updates.
+
+#[test]
+fn voters_update_their_details() {
+    let s = Setup::new();
+    let voter = s.voter();
+
+    s.client
+        .update_voter(&s.str("New name"), &s.str(OTHER_CID), &voter);
+    s.client.set_voter_profile(&s.profile(), &voter);
+
+    let record = s.client.get_voter(&voter);
+    assert_eq!(record.name, s.str("New name"));
+    assert_eq!(record.profile, s.profile());
+
+    let history = s.client.get_voter_history(&voter);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get_unchecked(0).name, s.str("Voter"));
+    assert_eq!(history.get_unchecked(0).changed_by, voter);
+
+    fails_with(
+        s.client.try_update_voter(&s.str(""), &s.str(CID), &voter),
+        Error::InvalidName,
+    );
+    fails_with(
+        s.client
+            .try_update_voter(&s.str("Name"), &s.str(CID), &Address::generate(&s.env)),
+        Error::NotFound,
+    );
+
+    s.deny_auths();
+    fails(
+        s.client
+            .try_update_voter(&s.str("Name"), &s.str(CID), &voter),
+    );
+}
+
+#[test]
+fn history_keeps_the_latest_revisions() {
+    let s = Setup::new();
+    let voter = s.voter();
+
+    for i in 0..MAX_REVISIONS + 2 {
+        s.env.ledger().set_timestamp(u64::from(i));
+        s.client.update_voter(&s.str("Voter"), &s.str(CID), &voter);
+    }
+
+    let history = s.client.get_voter_history(&voter);
+    assert_eq!(history.len(), MAX_REVISIONS);
+    assert_eq!(history.get_unchecked(0).changed_at, 2);
+}
+
+#[test]
+fn pending_candidates_update_directly() {
+    let s = Setup::new();
+    let candidate = s.registered_candidate();
+
+    s.client
+        .update_candidate(&s.str("Renamed"), &s.str(OTHER_CID), &candidate);
+    assert_eq!(s.client.get_candidate(&candidate).name, s.str("Renamed"));
+    assert_eq!(s.client.get_pending_candidate_update(&candidate), None);
+    assert_eq!(s.client.get_candidate_history(&candidate).len(), 1);
+}
+
+#[test]
+fn approved_candidates_stage_updates_for_review() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+
+    fails_with(
+        s.client
+            .try_approve_candidate_update(&candidate, &s.str("ok")),
+        Error::NotFound,
+    );
+
+    s.client
+        .update_candidate(&s.str("Renamed"), &s.str(OTHER_CID), &candidate);
+    s.client.set_candidate_profile(&s.profile(), &candidate);
+    assert_eq!(s.client.get_candidate(&candidate).name, s.str("Candidate"));
+
+    let staged = s.client.get_pending_candidate_update(&candidate).unwrap();
+    assert_eq!(staged.name, s.str("Renamed"));
+    assert_eq!(staged.profile, s.profile());
+
+    s.client
+        .approve_candidate_update(&candidate, &s.str("Looks good"));
+    let record = s.client.get_candidate(&candidate);
+    assert_eq!(record.name, s.str("Renamed"));
+    assert_eq!(record.profile.position, s.str("Treasurer"));
+    assert_eq!(s.client.get_pending_candidate_update(&candidate), None);
+    assert_eq!(s.client.get_candidate_history(&candidate).len(), 1);
+
+    s.client
+        .update_candidate(&s.str("Again"), &s.str(CID), &candidate);
+    s.client
+        .reject_candidate_update(&candidate, &s.str("Not allowed"));
+    assert_eq!(s.client.get_candidate(&candidate).name, s.str("Renamed"));
+    fails_with(
+        s.client
+            .try_reject_candidate_update(&candidate, &s.str("ok")),
+        Error::NotFound,
+    );
+}
+
+#[test]
+fn profiles_are_validated() {
+    let s = Setup::new();
+    let candidate = s.registered_candidate();
+
+    let mut profile = s.profile();
+    profile.manifesto = s.str("manifesto.pdf");
+    fails_with(
+        s.client.try_set_candidate_profile(&profile, &candidate),
+        Error::InvalidCid,
+    );
+
+    let mut profile = s.profile();
+    profile.metadata.set(s.str(""), s.str("value"));
+    fails_with(
+        s.client.try_set_candidate_profile(&profile, &candidate),
+        Error::InvalidProfile,
+    );
+}
+
+// Election settings.
+
+#[test]
+fn settings_are_fixed_once_voting_starts() {
+    let s = Setup::new();
+    let stranger = Address::generate(&s.env);
+
+    fails_with(s.client.try_set_seats(&0, &s.owner), Error::InvalidSeats);
+    fails(s.client.try_set_seats(&2, &stranger));
+    s.client.set_seats(&2, &s.owner);
+    s.client
+        .set_voting_method(&VotingMethod::Approval, &s.owner);
+    s.client.set_allow_revote(&true, &s.owner);
+    assert_eq!(s.client.get_seats(), 2);
+    assert_eq!(s.client.get_voting_method(), VotingMethod::Approval);
+    assert!(s.client.get_allow_revote());
+
+    s.open();
+    fails_with(s.client.try_set_seats(&3, &s.owner), Error::VotingStarted);
+    fails_with(
+        s.client
+            .try_set_voting_method(&VotingMethod::Ranked, &s.owner),
+        Error::VotingStarted,
+    );
+    fails_with(
+        s.client.try_set_allow_revote(&false, &s.owner),
+        Error::VotingStarted,
+    );
+}
+
+#[test]
+fn rescheduling_cannot_reopen_settings_after_a_ballot() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.open();
+    s.client.vote(&candidate, &voter);
+
+    fails_with(
+        s.client
+            .try_set_voting_period(&(END + 100), &(END + 200), &s.owner),
+        Error::VotingStarted,
+    );
+    // The end time can still move.
+    s.client.set_voting_period(&START, &(END + 100), &s.owner);
+
+    // Even with the clock back before the start, a cast ballot keeps the
+    // settings fixed.
+    s.env.ledger().set_timestamp(START - 1);
+    fails_with(
+        s.client.try_set_allow_revote(&true, &s.owner),
+        Error::VotingStarted,
+    );
+    fails_with(
+        s.client
+            .try_set_voting_method(&VotingMethod::Approval, &s.owner),
+        Error::VotingStarted,
+    );
+    fails_with(s.client.try_set_seats(&3, &s.owner), Error::VotingStarted);
+    fails_with(
+        s.client
+            .try_set_withdrawal_policy(&WithdrawalPolicy::ReenableVoters, &s.owner),
+        Error::VotingStarted,
+    );
+    fails_with(
+        s.client.try_add_question(
+            &s.str("Late?"),
+            &vec![&s.env, s.str("Yes"), s.str("No")],
+            &s.owner,
+        ),
+        Error::VotingStarted,
+    );
+
+    s.env.ledger().set_timestamp(START);
+    fails(s.client.try_vote(&candidate, &voter));
+    assert_eq!(s.client.get_candidate(&candidate).vote_count, 1);
+}
+
+#[test]
+fn an_unscheduled_election_cannot_be_scheduled_after_a_ballot() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    // With no period set, voting is open at time 0.
+    s.client.vote(&candidate, &voter);
+
+    fails_with(
+        s.client.try_set_voting_period(&START, &END, &s.owner),
+        Error::VotingStarted,
+    );
+    fails_with(
+        s.client.try_set_allow_revote(&true, &s.owner),
+        Error::VotingStarted,
+    );
+}
+
+#[test]
+fn voting_period_must_be_ordered_and_set_by_the_owner() {
+    let s = Setup::new();
+
+    fails(s.client.try_set_voting_period(&END, &START, &s.owner));
+    fails(
+        s.client
+            .try_set_voting_period(&START, &END, &Address::generate(&s.env)),
+    );
+    s.schedule();
+    assert_eq!(s.client.get_voting_time(), vec![&s.env, START, END]);
+}
+
+// Voting.
+
+#[test]
+fn a_vote_is_counted_once_and_receipted() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.open();
+
+    let receipt = s.client.vote(&candidate, &voter);
+    assert_eq!(s.env.auths()[0].0, voter);
+    assert_eq!(receipt.election_id, 1);
+    assert_eq!(receipt.sequence, 1);
+
+    assert_eq!(s.client.get_candidate(&candidate).vote_count, 1);
+    assert!(s.client.get_voter(&voter).has_voted);
+    assert_eq!(s.client.get_receipt(&voter), Some(receipt.clone()));
+    assert_eq!(s.client.get_last_receipt(), Some(receipt.clone()));
+    assert_eq!(s.client.get_ballot_root(), receipt.hash);
+    assert!(s.client.verify_receipt(&receipt));
+
+    let ledger = s.client.get_all_voters_who_voted(&0, &10);
+    assert_eq!(ledger.len(), 1);
+    assert_eq!(ledger.get_unchecked(0).voter, voter);
+    assert_eq!(ledger.get_unchecked(0).voted_at, START);
+
+    fails(s.client.try_vote(&candidate, &voter));
+    assert_eq!(s.client.get_candidate(&candidate).vote_count, 1);
+}
+
+#[test]
+fn a_vote_publishes_a_ballot_event() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.open();
+
+    let receipt = s.client.vote(&candidate, &voter);
+
+    let (contract, topics, data) = s.env.events().all().last().unwrap();
+    assert_eq!(contract, s.client.address);
+    assert_eq!(
+        topics,
+        vec![
+            &s.env,
+            symbol_short!("ballot").into_val(&s.env),
+            1u32.into_val(&s.env)
+        ]
+    );
+    let (sequence, who, ballot, hash): (u32, Address, Ballot, BytesN<32>) = data.into_val(&s.env);
+    assert_eq!(sequence, 1);
+    assert_eq!(who, voter);
+    assert_eq!(ballot, Ballot::Candidate(candidate));
+    assert_eq!(hash, receipt.hash);
+}
+
+#[test]
+fn voting_is_only_open_during_the_period() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+
+    s.schedule();
+    s.env.ledger().set_timestamp(START - 1);
+    fails(s.client.try_vote(&candidate, &voter));
+    fails(s.client.try_abstain(&voter));
+
+    s.close();
+    fails(s.client.try_vote(&candidate, &voter));
+
+    s.env.ledger().set_timestamp(END);
+    s.client.vote(&candidate, &voter);
+}
+
+#[test]
+fn a_vote_needs_the_voter_signature() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.open();
+
+    s.deny_auths();
+    fails(s.client.try_vote(&candidate, &voter));
+    assert_eq!(s.client.get_voted_count(), 0);
+}
+
+#[test]
+fn only_approved_voters_vote_for_approved_candidates() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let pending_candidate = s.registered_candidate();
+    let voter = s.voter();
+    let pending_voter = s.registered_voter();
+    s.open();
+
+    fails(s.client.try_vote(&candidate, &pending_voter));
+    fails(s.client.try_vote(&candidate, &Address::generate(&s.env)));
+    fails(s.client.try_vote(&pending_candidate, &voter));
+    fails(s.client.try_vote(&Address::generate(&s.env), &voter));
+    assert_eq!(s.client.get_voted_count(), 0);
+}
+
+#[test]
+fn voting_by_candidate_id() {
+    let s = Setup::new();
+    s.candidate();
+    let second = s.candidate();
+    let voter = s.voter();
+    s.open();
+
+    fails_with(s.client.try_vote_for_id(&3, &voter), Error::NotFound);
+    s.client.vote_for_id(&2, &voter);
+    assert_eq!(s.client.get_candidate(&second).vote_count, 1);
+}
+
+#[test]
+fn revoting_replaces_the_ballot() {
+    let s = Setup::new();
+    let (a, b) = (s.candidate(), s.candidate());
+    let voter = s.voter();
+    s.client.set_allow_revote(&true, &s.owner);
+    s.open();
+
+    let first = s.client.vote(&a, &voter);
+    let second = s.client.vote(&b, &voter);
+    s.client.abstain(&voter);
+
+    assert_eq!(s.client.get_candidate(&a).vote_count, 0);
+    assert_eq!(s.client.get_candidate(&b).vote_count, 0);
+    let results = s.client.get_results();
+    assert_eq!(results.abstentions, 1);
+    assert_eq!(results.turnout, 1);
+    assert_eq!(s.client.get_all_voters_who_voted(&0, &10).len(), 1);
+
+    assert_eq!(second.sequence, 2);
+    assert!(s.client.verify_receipt(&first));
+    assert_eq!(s.client.get_receipt(&voter).unwrap().sequence, 3);
+}
+
+#[test]
+fn abstaining_counts_towards_turnout_only() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let (a, b) = (s.voter(), s.voter());
+    s.voter();
+    s.open();
+
+    s.client.abstain(&a);
+    s.client.vote(&candidate, &b);
+    fails(s.client.try_abstain(&a));
+
+    let results = s.client.get_results();
+    assert_eq!(results.abstentions, 1);
+    assert_eq!(results.turnout, 2);
+    assert_eq!(results.eligible, 3);
+    assert_eq!(results.candidates.get_unchecked(0).vote_count, 1);
+}
+
+#[test]
+fn approval_ballots() {
+    let s = Setup::new();
+    let (a, b, c) = (s.candidate(), s.candidate(), s.candidate());
+    let (x, y) = (s.voter(), s.voter());
+    let pending = s.registered_candidate();
+    s.client
+        .set_voting_method(&VotingMethod::Approval, &s.owner);
+    s.open();
+
+    fails_with(
+        s.client.try_vote_ranked(&vec![&s.env, a.clone()], &x),
+        Error::WrongVotingMethod,
+    );
+    fails_with(
+        s.client.try_vote_approval(&vec![&s.env], &x),
+        Error::InvalidBallot,
+    );
+    fails_with(
+        s.client
+            .try_vote_approval(&vec![&s.env, a.clone(), a.clone()], &x),
+        Error::InvalidBallot,
+    );
+    fails_with(
+        s.client
+            .try_vote_approval(&vec![&s.env, a.clone(), pending], &x),
+        Error::InvalidBallot,
+    );
+
+    s.client
+        .vote_approval(&vec![&s.env, a.clone(), b.clone()], &x);
+    s.client.vote_approval(&vec![&s.env, b.clone()], &y);
+    assert_eq!(s.client.get_candidate(&a).vote_count, 1);
+    assert_eq!(s.client.get_candidate(&b).vote_count, 2);
+    assert_eq!(s.client.get_candidate(&c).vote_count, 0);
+}
+
+#[test]
+fn ranked_ballots_count_first_preferences() {
+    let s = Setup::new();
+    let (a, b) = (s.candidate(), s.candidate());
+    let voter = s.voter();
+    s.client.set_voting_method(&VotingMethod::Ranked, &s.owner);
+    s.open();
+
+    fails_with(
+        s.client.try_vote_approval(&vec![&s.env, a.clone()], &voter),
+        Error::WrongVotingMethod,
+    );
+    s.client
+        .vote_ranked(&vec![&s.env, b.clone(), a.clone()], &voter);
+    assert_eq!(s.client.get_candidate(&a).vote_count, 0);
+    assert_eq!(s.client.get_candidate(&b).vote_count, 1);
+}
+
+// Results.
+
+#[test]
+fn plurality_winners() {
+    let s = Setup::new();
+    let (a, b, c) = (s.candidate(), s.candidate(), s.candidate());
+    s.client.set_seats(&2, &s.owner);
+    let voters: std::vec::Vec<Address> = (0..5).map(|_| s.voter()).collect();
+    s.open();
+
+    for (voter, choice) in voters.iter().zip([&b, &b, &c, &a, &b]) {
+        s.client.vote(choice, voter);
+    }
+    assert_eq!(s.client.get_current_voting_status().candidate_address, b);
+    fails_with(s.client.try_get_winners(), Error::VotingNotEnded);
+    fails(s.client.try_get_winning_candidate());
+
+    s.close();
+    let winners = s.client.get_winners();
+    assert_eq!(winners.len(), 2);
+    assert_eq!(winners.get_unchecked(0).candidate_address, b);
+    // `a` and `c` tie; the earlier-registered candidate wins the tie.
+    assert_eq!(winners.get_unchecked(1).candidate_address, a);
+    assert_eq!(s.client.get_winning_candidate().candidate_address, b);
+}
+
+#[test]
+fn ranked_winners_use_transfers() {
+    let s = Setup::new();
+    let (c, a, b) = (s.candidate(), s.candidate(), s.candidate());
+    s.client.set_voting_method(&VotingMethod::Ranked, &s.owner);
+    let voters: std::vec::Vec<Address> = (0..5).map(|_| s.voter()).collect();
+    s.open();
+
+    let rankings = [
+        vec![&s.env, c.clone()],
+        vec![&s.env, c.clone(), b.clone()],
+        vec![&s.env, a.clone()],
+        vec![&s.env, a.clone(), c.clone()],
+        vec![&s.env, b.clone(), a.clone()],
+    ];
+    for (voter, ranking) in voters.iter().zip(rankings.iter()) {
+        s.client.vote_ranked(ranking, voter);
+    }
+    s.close();
+
+    // `c` leads on first preferences, but once `b` is eliminated their
+    // ballot moves to `a`, who reaches the quota of 3.
+    assert_eq!(s.client.get_current_voting_status().candidate_address, c);
+    let winners = s.client.get_winners();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(winners.get_unchecked(0).candidate_address, a);
+}
+
+#[test]
+fn leading_candidate_without_candidates() {
+    let s = Setup::new();
+    assert_eq!(s.client.get_current_voting_status().ipfs, s.str("NotFound"));
+    s.schedule();
+    s.close();
+    assert!(s.client.get_winners().is_empty());
+}
+
+#[test]
+fn tampered_receipts_do_not_verify() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.open();
+
+    let mut receipt = s.client.vote(&candidate, &voter);
+    receipt.hash = BytesN::from_array(&s.env, &[0; 32]);
+    assert!(!s.client.verify_receipt(&receipt));
+}
+
+#[test]
+fn participation_is_paginated() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voters: std::vec::Vec<Address> = (0..3).map(|_| s.voter()).collect();
+    s.open();
+
+    for voter in &voters {
+        s.client.vote(&candidate, voter);
+    }
+
+    assert_eq!(s.client.get_voted_count(), 3);
+    let first = s.client.get_all_voters_who_voted(&0, &2);
+    let rest = s.client.get_all_voters_who_voted(&2, &2);
+    assert_eq!(first.len(), 2);
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest.get_unchecked(0).voter, voters[2]);
+    assert!(s.client.get_all_voters_who_voted(&5, &2).is_empty());
+}
+
+// Referendum questions.
+
+#[test]
+fn questions_are_added_before_voting() {
+    let s = Setup::new();
+    let yes_no = vec![&s.env, s.str("Yes"), s.str("No")];
+
+    assert_eq!(
+        s.client
+            .add_question(&s.str("Raise dues?"), &yes_no, &s.owner),
+        0
+    );
+    fails(
+        s.client
+            .try_add_question(&s.str("Raise dues?"), &yes_no, &Address::generate(&s.env)),
+    );
+    fails_with(
+        s.client
+            .try_add_question(&s.str("Raise dues?"), &vec![&s.env, s.str("Yes")], &s.owner),
+        Error::InvalidQuestion,
+    );
+    fails_with(
+        s.client.try_add_question(
+            &s.str("Raise dues?"),
+            &vec![&s.env, s.str("Yes"), s.str("Yes")],
+            &s.owner,
+        ),
+        Error::InvalidQuestion,
+    );
+
+    for _ in 1..MAX_QUESTIONS {
+        s.client.add_question(&s.str("Another?"), &yes_no, &s.owner);
+    }
+    fails_with(
+        s.client
+            .try_add_question(&s.str("One too many?"), &yes_no, &s.owner),
+        Error::InvalidQuestion,
+    );
+    assert_eq!(s.client.get_questions().len(), MAX_QUESTIONS);
+
+    s.open();
+    fails_with(
+        s.client
+            .try_add_question(&s.str("Late?"), &yes_no, &s.owner),
+        Error::VotingStarted,
+    );
+}
+
+#[test]
+fn answering_questions() {
+    let s = Setup::new();
+    let voter = s.voter();
+    let pending = s.registered_voter();
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+
+    s.schedule();
+    s.env.ledger().set_timestamp(START - 1);
+    fails(s.client.try_vote_questions(&vec![&s.env, (0, 0)], &voter));
+    s.env.ledger().set_timestamp(START);
+
+    fails_with(
+        s.client.try_vote_questions(&vec![&s.env], &voter),
+        Error::InvalidBallot,
+    );
+    fails_with(
+        s.client.try_vote_questions(&vec![&s.env, (0, 2)], &voter),
+        Error::InvalidBallot,
+    );
+    fails_with(
+        s.client.try_vote_questions(&vec![&s.env, (1, 0)], &voter),
+        Error::NotFound,
+    );
+    fails_with(
+        s.client.try_vote_questions(&vec![&s.env, (0, 0)], &pending),
+        Error::InvalidStatus,
+    );
+
+    s.client.vote_questions(&vec![&s.env, (0, 1)], &voter);
+    assert_eq!(s.client.get_question_tally(&0), vec![&s.env, 0, 1]);
+    assert_eq!(s.client.get_answer(&0, &voter), Some(1));
+    fails_with(
+        s.client.try_vote_questions(&vec![&s.env, (0, 0)], &voter),
+        Error::AlreadyAnswered,
+    );
+}
+
+#[test]
+fn answers_can_change_when_revoting_is_allowed() {
+    let s = Setup::new();
+    let voter = s.voter();
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.client.set_allow_revote(&true, &s.owner);
+    s.open();
+
+    s.client.vote_questions(&vec![&s.env, (0, 1)], &voter);
+    s.client.vote_questions(&vec![&s.env, (0, 0)], &voter);
+    assert_eq!(s.client.get_question_tally(&0), vec![&s.env, 1, 0]);
+}
+
+#[test]
+fn answering_counts_towards_turnout_once() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let (voter, other) = (s.voter(), s.voter());
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.client.add_question(
+        &s.str("Meeting day?"),
+        &vec![&s.env, s.str("Monday"), s.str("Friday")],
+        &s.owner,
+    );
+    s.open();
+
+    s.client.vote_questions(&vec![&s.env, (0, 0)], &voter);
+    assert_eq!(s.client.get_voted_count(), 1);
+    assert_eq!(s.client.get_results().turnout, 1);
+    let ledger = s.client.get_all_voters_who_voted(&0, &10);
+    assert_eq!(ledger.len(), 1);
+    assert_eq!(ledger.get_unchecked(0).voter, voter);
+    assert!(!s.client.get_voter(&voter).has_voted);
+
+    // Further answers and a candidate ballot add no second entry.
+    s.client.vote_questions(&vec![&s.env, (1, 1)], &voter);
+    s.client.vote(&candidate, &voter);
+    s.client.vote(&candidate, &other);
+    s.client.vote_questions(&vec![&s.env, (0, 1)], &other);
+    assert_eq!(s.client.get_voted_count(), 2);
+    let ledger = s.client.get_all_voters_who_voted(&0, &10);
+    assert_eq!(ledger.get_unchecked(1).voter, other);
+}
+
+#[test]
+fn answers_extend_the_ballot_chain() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.open();
+
+    let ballot = s.client.vote(&candidate, &voter);
+    let answers = vec![&s.env, (0u32, 1u32)];
+    let receipt = s.client.vote_questions(&answers, &voter);
+    assert_eq!(receipt.sequence, 2);
+    assert_eq!(
+        receipt.hash,
+        receipt::link_answers(&s.env, &ballot.hash, 2, &voter, &answers)
+    );
+    assert_eq!(s.client.get_ballot_root(), receipt.hash);
+    assert_eq!(s.client.get_last_receipt(), Some(receipt.clone()));
+    assert!(s.client.verify_receipt(&receipt));
+    // The voter's ballot receipt still stands for their ballot.
+    assert_eq!(s.client.get_receipt(&voter), Some(ballot));
+
+    let (contract, topics, data) = s.env.events().all().last().unwrap();
+    assert_eq!(contract, s.client.address);
+    assert_eq!(
+        topics,
+        vec![
+            &s.env,
+            symbol_short!("answers").into_val(&s.env),
+            1u32.into_val(&s.env)
+        ]
+    );
+    let (sequence, who, published, hash): (u32, Address, Vec<(u32, u32)>, BytesN<32>) =
+        data.into_val(&s.env);
+    assert_eq!(sequence, 2);
+    assert_eq!(who, voter);
+    assert_eq!(published, answers);
+    assert_eq!(hash, receipt.hash);
+}
+
+#[test]
+fn full_counters_fail_instead_of_wrapping() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let (voter, other) = (s.voter(), s.voter());
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.open();
+    s.env.as_contract(&s.client.address, || {
+        let storage = s.env.storage().persistent();
+        let full = Receipt {
+            election_id: 1,
+            sequence: u32::MAX,
+            hash: receipt::genesis(&s.env),
+        };
+        storage.set(&(LAST_RECEIPT, 1u32), &full);
+        storage.set(&Questions::Tally(1, 0), &vec![&s.env, u64::MAX, 0u64]);
+    });
+
+    fails_with(s.client.try_vote(&candidate, &voter), Error::Overflow);
+    fails_with(
+        s.client.try_vote_questions(&vec![&s.env, (0, 0)], &other),
+        Error::Overflow,
+    );
+    assert_eq!(s.client.get_candidate(&candidate).vote_count, 0);
+}
+
+// Finalizing and archiving.
+
+#[test]
+fn finalizing_freezes_the_ballot_root() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+
+    fails_with(
+        s.client.try_finalize_election(&s.owner),
+        Error::VotingNotEnded,
+    );
+    s.open();
+    let receipt = s.client.vote(&candidate, &voter);
+    fails_with(
+        s.client.try_finalize_election(&s.owner),
+        Error::VotingNotEnded,
+    );
+
+    s.close();
+    fails(s.client.try_finalize_election(&voter));
+    assert_eq!(s.client.finalize_election(&s.owner), receipt.hash);
+    assert!(s.client.is_election_finalized());
+    assert_eq!(s.client.get_ballot_root(), receipt.hash);
+
+    fails_with(
+        s.client.try_finalize_election(&s.owner),
+        Error::ElectionFinalized,
+    );
+    fails_with(
+        s.client.try_set_voting_period(&START, &(END * 2), &s.owner),
+        Error::ElectionFinalized,
+    );
+}
+
+#[test]
+fn archiving_starts_a_fresh_election() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.open();
+    s.client.vote(&candidate, &voter);
+    let receipt = s.client.vote_questions(&vec![&s.env, (0, 0)], &voter);
+    fails_with(
+        s.client.try_archive_election(&s.owner),
+        Error::VotingInProgress,
+    );
+    fails_with(
+        s.client.try_reset_contract(&s.owner),
+        Error::VotingInProgress,
+    );
+    assert!(!s.client.is_election_finalized());
+    s.close();
+
+    fails(s.client.try_archive_election(&voter));
+    fails(s.client.try_get_archived_election(&1));
+    assert_eq!(s.client.archive_election(&s.owner), 1);
+
+    assert_eq!(s.client.get_current_election(), 2);
+    assert!(s.client.get_all_registered_voters().is_empty());
+    assert!(s.client.get_all_registered_candidates().is_empty());
+    assert_eq!(s.client.get_voted_count(), 0);
+    assert_eq!(
+        s.client.get_ballot_root(),
+        BytesN::from_array(&s.env, &[0; 32])
+    );
+    assert!(!s.client.is_election_finalized());
+
+    let archived = s.client.get_archived_election(&1);
+    assert_eq!(archived.election_id, 1);
+    assert_eq!((archived.start_time, archived.end_time), (START, END));
+    assert_eq!(archived.archived_at, END + 1);
+    assert_eq!(archived.registered_voters, 1);
+    assert_eq!(archived.approved_candidates, 1);
+    assert_eq!(archived.ballots_cast, 2);
+    assert_eq!(archived.ballot_root, receipt.hash);
+
+    assert_eq!(s.client.get_archived_voters(&1).len(), 1);
+    assert_eq!(s.client.get_archived_candidates(&1).len(), 1);
+    assert!(s.client.get_archived_voter(&1, &voter).has_voted);
+    assert_eq!(
+        s.client.get_archived_candidate(&1, &candidate).vote_count,
+        1
+    );
+    assert!(s.client.get_archived_voter_history(&1, &voter).is_empty());
+    assert!(s
+        .client
+        .get_archived_candidate_history(&1, &candidate)
+        .is_empty());
+    assert_eq!(s.client.get_archived_results(&1).turnout, 1);
+    assert_eq!(s.client.get_archived_questions(&1).len(), 1);
+    assert_eq!(
+        s.client.get_archived_question_tally(&1, &0),
+        vec![&s.env, 1, 0]
+    );
+    assert_eq!(s.client.get_archived_voters_who_voted(&1, &0, &10).len(), 1);
+    assert!(s.client.verify_receipt(&receipt));
+    s.client.extend_archive_ttl(&1, &0, &10);
+
+    let returning = s.registered_voter();
+    assert_eq!(s.client.get_voter(&returning).register_id, 1);
+    assert_eq!(s.client.get_voter(&voter).ipfs, s.str("NotFound"));
+}
+
+#[test]
+fn extending_an_archive_keeps_every_receipt_and_the_contract_live() {
+    let s = Setup::new();
+    let candidate = s.candidate();
+    let voter = s.voter();
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.client.set_allow_revote(&true, &s.owner);
+    s.open();
+    let first = s.client.vote(&candidate, &voter);
+    s.client.vote(&candidate, &voter);
+    let answers = s.client.vote_questions(&vec![&s.env, (0, 0)], &voter);
+    s.close();
+    s.client.archive_election(&s.owner);
+
+    s.client.extend_archive_ttl(&1, &0, &10);
+
+    s.env.as_contract(&s.client.address, || {
+        let max_ttl = s.env.storage().max_ttl();
+        let persistent = s.env.storage().persistent();
+        for sequence in [first.sequence, 2, answers.sequence] {
+            assert_eq!(persistent.get_ttl(&Ballots::Chain(1, sequence)), max_ttl);
+        }
+        for key in [OWNER, ELECTION_ID, VERSION] {
+            assert_eq!(persistent.get_ttl(&key), max_ttl);
+        }
+        assert_eq!(s.env.storage().instance().get_ttl(), max_ttl);
+    });
+}
+
+#[test]
+fn archived_getters_reject_the_live_election() {
+    let s = Setup::new();
+
+    fails(s.client.try_get_archived_voters(&1));
+    fails(s.client.try_get_archived_results(&0));
+    fails(s.client.try_extend_archive_ttl(&1, &0, &10));
+}
+
+#[test]
+fn reset_contract_archives() {
+    let s = Setup::new();
+    s.voter();
+    s.schedule();
+
+    s.client.reset_contract(&s.owner);
+    assert_eq!(s.client.get_current_election(), 2);
+    assert_eq!(s.client.get_archived_voters(&1).len(), 1);
+    fails(s.client.try_reset_contract(&Address::generate(&s.env)));
+}
+
+// Properties.
+
+const VOTERS: u8 = 6;
+const CANDIDATES: u8 = 4;
+
+#[derive(Clone, Debug)]
+enum Op {
+    RegisterVoter(u8),
+    ApproveVoter(u8),
+    RejectVoter(u8),
+    RevokeVoter(u8),
+    RegisterCandidate(u8),
+    ApproveCandidate(u8),
+    WithdrawCandidate(u8),
+    Vote(u8, u8),
+    VoteApproval(u8, std::vec::Vec<u8>),
+    VoteRanked(u8, std::vec::Vec<u8>),
+    Abstain(u8),
+}
+
+/// Registrar calls, withdrawals included.
+fn roll_op() -> impl Strategy<Value = Op> {
+    let voter = 0..VOTERS;
+    let candidate = 0..CANDIDATES;
+    prop_oneof![
+        voter.clone().prop_map(Op::RegisterVoter),
+        voter.clone().prop_map(Op::ApproveVoter),
+        voter.clone().prop_map(Op::RejectVoter),
+        voter.prop_map(Op::RevokeVoter),
+        candidate.clone().prop_map(Op::RegisterCandidate),
+        candidate.clone().prop_map(Op::ApproveCandidate),
+        candidate.prop_map(Op::WithdrawCandidate),
+    ]
+}
+
+/// Ballots of every kind, whichever method the election uses.
+fn ballot_op() -> impl Strategy<Value = Op> {
+    let voter = 0..VOTERS;
+    let candidate = 0..CANDIDATES;
+    let choices = prop::collection::vec(candidate.clone(), 1..=3);
+    prop_oneof![
+        (voter.clone(), candidate).prop_map(|(v, c)| Op::Vote(v, c)),
+        (voter.clone(), choices.clone()).prop_map(|(v, c)| Op::VoteApproval(v, c)),
+        (voter.clone(), choices).prop_map(|(v, c)| Op::VoteRanked(v, c)),
+        voter.prop_map(Op::Abstain),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![roll_op(), ballot_op()]
+}
+
+fn method() -> impl Strategy<Value = VotingMethod> {
+    prop_oneof![
+        Just(VotingMethod::Plurality),
+        Just(VotingMethod::Approval),
+        Just(VotingMethod::Ranked),
+    ]
+}
+
+fn policy() -> impl Strategy<Value = WithdrawalPolicy> {
+    prop_oneof![
+        Just(WithdrawalPolicy::VoidVotes),
+        Just(WithdrawalPolicy::ReenableVoters),
+    ]
+}
+
+/// Each voter's counted ballot, as the candidates it counts for; empty for
+/// an abstention.
+type Counted = std::collections::BTreeMap<u8, std::vec::Vec<u8>>;
+
+/// Runs `ops` against a fresh contract with voting open. Returns how many
+/// ballots were accepted and the ballot each voter has counted now.
+fn run(s: &Setup, voters: &[Address], candidates: &[Address], ops: &[Op]) -> (u32, Counted) {
+    let ok = s.str("ok");
+    let choices = |indexes: &[u8]| {
+        let mut choices = vec![&s.env];
+        for &c in indexes {
+            choices.push_back(candidates[c as usize].clone());
+        }
+        choices
+    };
+    let mut ballots = 0;
+    let mut counted = Counted::new();
+    for op in ops {
+        let ballot = match op {
+            Op::RegisterVoter(v) => {
+                let _ =
+                    s.client
+                        .try_registerVoter(&s.str("Voter"), &s.str(CID), &voters[*v as usize]);
+                None
+            }
+            Op::ApproveVoter(v) => {
+                let _ = s.client.try_approve_voter(&voters[*v as usize], &ok);
+                None
+            }
+            Op::RejectVoter(v) => {
+                let _ = s.client.try_reject_voter(&voters[*v as usize], &ok);
+                None
+            }
+            Op::RevokeVoter(v) => {
+                let _ = s.client.try_revoke_voter(&voters[*v as usize], &ok);
+                None
+            }
+            Op::RegisterCandidate(c) => {
+                let _ = s.client.try_register_candidate(
+                    &s.str("Candidate"),
+                    &s.str(CID),
+                    &candidates[*c as usize],
+                );
+                None
+            }
+            Op::ApproveCandidate(c) => {
+                let _ = s
+                    .client
+                    .try_approve_candidate(&candidates[*c as usize], &ok);
+                None
+            }
+            Op::WithdrawCandidate(c) => {
+                let _ = s
+                    .client
+                    .try_withdraw_candidate(&candidates[*c as usize], &ok);
+                None
+            }
+            Op::Vote(v, c) => s
+                .client
+                .try_vote(&candidates[*c as usize], &voters[*v as usize])
+                .is_ok()
+                .then(|| (*v, std::vec![*c])),
+            Op::VoteApproval(v, c) => s
+                .client
+                .try_vote_approval(&choices(c), &voters[*v as usize])
+                .is_ok()
+                .then(|| (*v, c.clone())),
+            Op::VoteRanked(v, c) => s
+                .client
+                .try_vote_ranked(&choices(c), &voters[*v as usize])
+                .is_ok()
+                .then(|| (*v, std::vec![c[0]])),
+            Op::Abstain(v) => s
+                .client
+                .try_abstain(&voters[*v as usize])
+                .is_ok()
+                .then(|| (*v, std::vec![])),
+        };
+        // An accepted ballot replaces whatever the voter had counted before.
+        if let Some((voter, choices)) = ballot {
+            ballots += 1;
+            counted.insert(voter, choices);
+        }
+    }
+    (ballots, counted)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn random_elections_keep_their_books_straight(
+        ops in prop::collection::vec(op(), 1..40),
+        revote in any::<bool>(),
+        method in method(),
+        policy in policy(),
+    ) {
+        let s = Setup::unmetered();
+        let voters: std::vec::Vec<Address> =
+            (0..VOTERS).map(|_| Address::generate(&s.env)).collect();
+        let candidates: std::vec::Vec<Address> =
+            (0..CANDIDATES).map(|_| Address::generate(&s.env)).collect();
+        s.client.set_allow_revote(&revote, &s.owner);
+        s.client.set_voting_method(&method, &s.owner);
+        s.client.set_withdrawal_policy(&policy, &s.owner);
+        s.open();
+
+        let (ballots, counted) = run(&s, &voters, &candidates, &ops);
+
+        let registered_voters = s.client.get_all_registered_voters();
+        let registered_candidates = s.client.get_all_registered_candidates();
+        let results = s.client.get_results();
+        let turnout = s.client.get_voted_count();
+
+        // Every voter has exactly one ballot counted, for their candidates
+        // or as an abstention, however often they voted again.
+        prop_assert_eq!(counted.len(), turnout as usize);
+        let abstentions = counted.values().filter(|c| c.is_empty()).count();
+        prop_assert_eq!(results.abstentions as usize, abstentions);
+        for (i, candidate) in candidates.iter().enumerate() {
+            let votes = counted.values().flatten().filter(|&&c| c as usize == i).count();
+            prop_assert_eq!(s.client.get_candidate(candidate).vote_count, votes as u64);
+        }
+
+        // The participation ledger lists each voter who voted, once.
+        let ledger = s.client.get_all_voters_who_voted(&0, &MAX_PAGE_SIZE);
+        prop_assert_eq!(ledger.len(), turnout);
+        for voter in registered_voters.iter() {
+            let entries = ledger.iter().filter(|p| p.voter == voter.voter_address).count();
+            prop_assert_eq!(entries, usize::from(voter.has_voted));
+        }
+
+        // The approved rolls hold exactly the records marked approved.
+        let approved_voters = s.client.get_all_approved_voters();
+        for voter in registered_voters.iter() {
+            prop_assert_eq!(voter.status == APPROVED, approved_voters.contains(&voter));
+        }
+        prop_assert_eq!(results.eligible, approved_voters.len());
+        let approved_candidates = s.client.get_all_approved_candidates();
+        for candidate in registered_candidates.iter() {
+            prop_assert_eq!(
+                candidate.status == APPROVED,
+                approved_candidates.contains(&candidate)
+            );
+        }
+
+        // Ids are handed out from 1 in registration order.
+        for (i, voter) in registered_voters.iter().enumerate() {
+            prop_assert_eq!(voter.register_id, i as u32 + 1);
+        }
+        for (i, candidate) in registered_candidates.iter().enumerate() {
+            prop_assert_eq!(candidate.register_id, i as u32 + 1);
+        }
+
+        // Every accepted ballot, revotes included, extends the receipt chain.
+        match s.client.get_last_receipt() {
+            Some(last) => {
+                prop_assert_eq!(last.sequence, ballots);
+                prop_assert_eq!(s.client.get_ballot_root(), last.hash.clone());
+                prop_assert!(s.client.verify_receipt(&last));
+            }
+            None => prop_assert_eq!(ballots, 0),
+        }
+    }
+
+    #[test]
+    fn ranked_elections_fill_every_seat_they_can(
+        rankings in prop::collection::vec(
+            prop::sample::subsequence((0..CANDIDATES).collect::<std::vec::Vec<_>>(), 1..=CANDIDATES as usize)
+                .prop_shuffle(),
+            0..8,
+        ),
+        approved in 1..=CANDIDATES,
+        seats in 1..=CANDIDATES as u32,
+    ) {
+        let s = Setup::unmetered();
+        let candidates: std::vec::Vec<Address> =
+            (0..approved).map(|_| s.candidate()).collect();
+        s.client.set_voting_method(&VotingMethod::Ranked, &s.owner);
+        s.client.set_seats(&seats, &s.owner);
+        s.open();
+
+        for ranking in &rankings {
+            let mut ballot = vec![&s.env];
+            for &c in ranking.iter().filter(|&&c| c < approved) {
+                ballot.push_back(candidates[c as usize].clone());
+            }
+            if !ballot.is_empty() {
+                s.client.vote_ranked(&ballot, &s.voter());
+            }
+        }
+        s.close();
+
+        let winners = s.client.get_winners();
+        prop_assert_eq!(winners.len(), seats.min(u32::from(approved)));
+        for (i, winner) in winners.iter().enumerate() {
+            prop_assert_eq!(winner.status.clone(), APPROVED);
+            prop_assert_eq!(winners.first_index_of(&winner), Some(i as u32));
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Whole elections run end to end, each checked against the sdk test snapshot
+//! committed under `test_snapshots/test/scenarios`. A snapshot holds the
+//! contract's storage, the ledger info and the recorded events and auths, so
+//! a change in what any entrypoint writes or publishes fails here. The new
+//! snapshot is written over the committed one when the test ends; review it
+//! with `git diff` and commit it if the change is intended.
+//!
+//! A scenario without a committed snapshot fails too. Run it once with
+//! `UPDATE_SNAPSHOTS=1` to record one.
+
+use std::path::PathBuf;
+
+use soroban_sdk::testutils::Snapshot;
+
+use super::*;
+
+/// The file the sdk writes this test's snapshot to, mirroring its
+/// `test_snapshots/{module path}/{test}.1.json` layout.
+fn snapshot_path() -> PathBuf {
+    let thread = std::thread::current();
+    let name = thread.name().expect("scenarios run as unit tests");
+    let mut path = PathBuf::from("test_snapshots");
+    path.extend(name.split("::"));
+    path.with_extension("1.json")
+}
+
+/// Compares the environment against the committed snapshot. A scenario that
+/// has no snapshot yet fails, unless `UPDATE_SNAPSHOTS=1` is set, in which
+/// case it passes and records one.
+fn assert_snapshot(s: &Setup) {
+    let path = snapshot_path();
+    let committed = match Snapshot::read_file(&path) {
+        Ok(committed) => committed,
+        Err(_) if std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") => return,
+        Err(e) => panic!(
+            "no snapshot at {}: {e}; run with UPDATE_SNAPSHOTS=1 to record it",
+            path.display()
+        ),
+    };
+    assert!(
+        committed == s.env.to_snapshot(),
+        "ledger state differs from {}; review the rewritten snapshot with `git diff`",
+        path.display()
+    );
+}
+
+#[test]
+fn plurality_election() {
+    let s = Setup::recorded();
+    let (a, b) = (s.candidate(), s.candidate());
+    let late = s.registered_candidate();
+    s.client
+        .reject_candidate(&late, &s.str("Nomination closed"));
+    let voters: std::vec::Vec<Address> = (0..5).map(|_| s.voter()).collect();
+    let unapproved = s.registered_voter();
+    s.client.reject_voter(&unapproved, &s.str("Not a member"));
+    s.open();
+
+    s.client.vote(&a, &voters[0]);
+    s.client.vote(&b, &voters[1]);
+    s.client.vote_for_id(&2, &voters[2]);
+    s.client.abstain(&voters[3]);
+    s.close();
+
+    s.client.finalize_election(&s.owner);
+    assert_eq!(s.client.get_winners().get_unchecked(0).candidate_address, b);
+    assert_snapshot(&s);
+}
+
+#[test]
+fn ranked_election_with_transfers() {
+    let s = Setup::recorded();
+    let (c, a, b) = (s.candidate(), s.candidate(), s.candidate());
+    s.client.set_voting_method(&VotingMethod::Ranked, &s.owner);
+    let voters: std::vec::Vec<Address> = (0..5).map(|_| s.voter()).collect();
+    s.open();
+
+    let rankings = [
+        vec![&s.env, c.clone()],
+        vec![&s.env, c.clone(), b.clone()],
+        vec![&s.env, a.clone()],
+        vec![&s.env, a.clone(), c.clone()],
+        vec![&s.env, b.clone(), a.clone()],
+    ];
+    for (voter, ranking) in voters.iter().zip(rankings.iter()) {
+        s.client.vote_ranked(ranking, voter);
+    }
+    s.close();
+
+    s.client.finalize_election(&s.owner);
+    assert_eq!(s.client.get_winners().get_unchecked(0).candidate_address, a);
+    assert_snapshot(&s);
+}
+
+#[test]
+fn approval_election_with_a_withdrawal() {
+    let s = Setup::recorded();
+    let (a, b, c) = (s.candidate(), s.candidate(), s.candidate());
+    let voters: std::vec::Vec<Address> = (0..3).map(|_| s.voter()).collect();
+    s.client.set_seats(&2, &s.owner);
+    s.client
+        .set_voting_method(&VotingMethod::Approval, &s.owner);
+    s.client
+        .set_withdrawal_policy(&WithdrawalPolicy::ReenableVoters, &s.owner);
+    s.client.set_allow_revote(&true, &s.owner);
+    s.open();
+
+    s.client
+        .vote_approval(&vec![&s.env, a.clone(), b.clone()], &voters[0]);
+    s.client.vote_approval(&vec![&s.env, a.clone()], &voters[1]);
+    s.client
+        .vote_approval(&vec![&s.env, b.clone(), c.clone()], &voters[2]);
+    s.client.vote_approval(&vec![&s.env, c.clone()], &voters[1]);
+    s.client.withdraw_candidacy(&a);
+    s.client.vote_approval(&vec![&s.env, b.clone()], &voters[0]);
+    s.close();
+
+    s.client.finalize_election(&s.owner);
+    assert_eq!(s.client.get_winners().len(), 2);
+    assert_snapshot(&s);
+}
+
+#[test]
+fn referendum_archived_into_the_next_election() {
+    let s = Setup::recorded();
+    let candidate = s.candidate();
+    let (x, y) = (s.voter(), s.voter());
+    s.client.add_question(
+        &s.str("Raise dues?"),
+        &vec![&s.env, s.str("Yes"), s.str("No")],
+        &s.owner,
+    );
+    s.client.add_question(
+        &s.str("Meeting day?"),
+        &vec![&s.env, s.str("Monday"), s.str("Friday"), s.str("Sunday")],
+        &s.owner,
+    );
+    s.open();
+
+    s.client.vote(&candidate, &x);
+    s.client.vote_questions(&vec![&s.env, (0, 0), (1, 2)], &x);
+    s.client.vote_questions(&vec![&s.env, (0, 1)], &y);
+    s.close();
+
+    s.client.finalize_election(&s.owner);
+    s.client.archive_election(&s.owner);
+    s.registered_voter();
+    assert_eq!(s.client.get_current_election(), 2);
+    assert_snapshot(&s);
+}
+
+#[test]
+fn registrar_workflow() {
+    let s = Setup::recorded();
+    let member = Address::generate(&s.env);
+    s.client
+        .set_allowlist(&vec![&s.env, member.clone()], &true, &s.owner);
+    s.client
+        .register_allowlisted(&s.str("Member"), &s.str(CID), &member);
+    s.client.import_voters(&vec![
+        &s.env,
+        (Address::generate(&s.env), s.str("Imported"), s.str(CID)),
+        (member.clone(), s.str("Member"), s.str(CID)),
+    ]);
+
+    let voter = s.registered_voter();
+    s.client.reject_voter(&voter, &s.str("Blurry photo"));
+    s.client
+        .reapply_voter(&s.str("Voter"), &s.str(OTHER_CID), &voter);
+    s.client.approve_voter(&voter, &s.str("ok"));
+    s.client.set_voter_profile(&s.profile(), &voter);
+    s.client.revoke_voter(&member, &s.str("Moved away"));
+
+    let candidate = s.candidate();
+    s.client
+        .update_candidate(&s.str("Renamed"), &s.str(OTHER_CID), &candidate);
+    s.client
+        .approve_candidate_update(&candidate, &s.str("Looks good"));
+    s.client.set_candidate_profile(&s.profile(), &candidate);
+    s.client
+        .reject_candidate_update(&candidate, &s.str("Use the official photo"));
+    assert_snapshot(&s);
+}
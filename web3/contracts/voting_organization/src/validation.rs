@@ -0,0 +1,117 @@
+//! Bounds on the user-supplied strings stored in voter and candidate
+//! records and referendum questions. Lengths are in bytes.
+//!
+//! An `ipfs` value must be a bare CID, not a gateway URL:
+//!
+//! - CIDv0: `Qm` followed by 44 base58btc characters (46 in total).
+//! - CIDv1: multibase `b` (lowercase base32, no padding), which always
+//!   starts `ba` as the version byte is 1.
+//!
+//! This also keeps the `"NotFound"` sentinel used by lookups from ever
+//! being a real record's `ipfs`.
+
+use soroban_sdk::{String, Vec};
+
+use crate::{Error, Profile};
+
+const MAX_NAME_LEN: u32 = 64;
+const MAX_MESSAGE_LEN: u32 = 256;
+
+const MAX_QUESTION_LEN: u32 = 256;
+const MAX_OPTIONS: u32 = 10;
+const MAX_OPTION_LEN: u32 = 64;
+
+const CID_V0_LEN: u32 = 46;
+const MIN_CID_V1_LEN: u32 = 8;
+const MAX_CID_LEN: u32 = 128;
+
+const MAX_PROFILE_FIELD_LEN: u32 = 64;
+const MAX_METADATA_ENTRIES: u32 = 8;
+const MAX_METADATA_KEY_LEN: u32 = 32;
+const MAX_METADATA_VALUE_LEN: u32 = 128;
+
+const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// A record's name and ipfs, as taken by registration and updates.
+pub(crate) fn details(name: &String, ipfs: &String) -> Result<(), Error> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(Error::InvalidName);
+    }
+    if !is_cid(ipfs) {
+        return Err(Error::InvalidCid);
+    }
+    Ok(())
+}
+
+/// A registrar's note on a decision. May be empty.
+pub(crate) fn message(message: &String) -> Result<(), Error> {
+    if message.len() > MAX_MESSAGE_LEN {
+        return Err(Error::InvalidMessage);
+    }
+    Ok(())
+}
+
+/// A referendum question needs text and between 2 and `MAX_OPTIONS`
+/// distinct, non-empty options.
+pub(crate) fn question(text: &String, options: &Vec<String>) -> Result<(), Error> {
+    if text.is_empty() || text.len() > MAX_QUESTION_LEN {
+        return Err(Error::InvalidQuestion);
+    }
+    if options.len() < 2 || options.len() > MAX_OPTIONS {
+        return Err(Error::InvalidQuestion);
+    }
+    for (i, option) in options.iter().enumerate() {
+        if option.is_empty() || option.len() > MAX_OPTION_LEN {
+            return Err(Error::InvalidQuestion);
+        }
+        if options.iter().skip(i + 1).any(|other| other == option) {
+            return Err(Error::InvalidQuestion);
+        }
+    }
+    Ok(())
+}
+
+/// All `Profile` fields are optional, but `manifesto` and `image` must be
+/// CIDs when set and metadata keys may not be empty.
+pub(crate) fn profile(profile: &Profile) -> Result<(), Error> {
+    for cid in [&profile.manifesto, &profile.image] {
+        if !cid.is_empty() && !is_cid(cid) {
+            return Err(Error::InvalidCid);
+        }
+    }
+
+    let within = |value: &String, max: u32| value.len() <= max;
+    let valid = within(&profile.affiliation, MAX_PROFILE_FIELD_LEN)
+        && within(&profile.position, MAX_PROFILE_FIELD_LEN)
+        && within(&profile.contact, MAX_PROFILE_FIELD_LEN)
+        && profile.metadata.len() <= MAX_METADATA_ENTRIES
+        && profile.metadata.iter().all(|(key, value)| {
+            !key.is_empty()
+                && within(&key, MAX_METADATA_KEY_LEN)
+                && within(&value, MAX_METADATA_VALUE_LEN)
+        });
+
+    if !valid {
+        return Err(Error::InvalidProfile);
+    }
+    Ok(())
+}
+
+fn is_cid(value: &String) -> bool {
+    let len = value.len();
+    if len > MAX_CID_LEN {
+        return false;
+    }
+
+    let mut buf = [0u8; MAX_CID_LEN as usize];
+    let bytes = &mut buf[..len as usize];
+    value.copy_into_slice(bytes);
+
+    match bytes {
+        [b'Q', b'm', rest @ ..] if len == CID_V0_LEN => rest.iter().all(|c| BASE58.contains(c)),
+        [b'b', b'a', rest @ ..] if len >= MIN_CID_V1_LEN => rest
+            .iter()
+            .all(|c| c.is_ascii_lowercase() || (b'2'..=b'7').contains(c)),
+        _ => false,
+    }
+}
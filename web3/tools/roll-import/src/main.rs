@@ -0,0 +1,431 @@
+//! Turns a membership export into what `VotingOrganization` needs to load
+//! the voter roll.
+//!
+//! The CSV must have a header row with `address`, `name` and `ipfs` columns.
+//!
+//! ```sh
+//! # one `import_voters` invocation per batch, ready to run
+//! roll-import invocations roll.csv --contract C... --source owner
+//!
+//! # Merkle root for `set_eligibility_root` plus a proof per member for
+//! # `register_with_proof`
+//! roll-import merkle roll.csv --out proofs.json
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::process::ExitCode;
+use std::{env, fs};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use stellar_strkey::Strkey;
+use stellar_xdr::curr::{AccountId, Hash, Limits, PublicKey, ScAddress, ScVal, Uint256, WriteXdr};
+
+// Matches `MAX_IMPORT_SIZE` in the contract.
+const MAX_IMPORT_SIZE: usize = 10;
+// Matches the contract's `validation` module.
+const MAX_NAME_LEN: usize = 64;
+const MAX_CID_LEN: usize = 128;
+
+const USAGE: &str = "usage:
+  roll-import invocations <roll.csv> --contract <id> [--source <identity>] [--network <name>] [--batch-size <n>]
+  roll-import merkle <roll.csv> [--out <proofs.json>]";
+
+struct Member {
+    address: String,
+    xdr: Vec<u8>,
+    name: String,
+    ipfs: String,
+}
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let (mode, path, options) = match args.as_slice() {
+        [mode, path, rest @ ..] => (mode.as_str(), path, parse_options(rest)?),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let members = read_roll(path)?;
+
+    match mode {
+        "invocations" => {
+            let contract = options
+                .get("contract")
+                .ok_or_else(|| format!("--contract is required\n{USAGE}"))?;
+            let source = options.get("source").map_or("owner", String::as_str);
+            let network = options.get("network").map_or("testnet", String::as_str);
+            let batch_size = match options.get("batch-size") {
+                Some(n) => n
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=MAX_IMPORT_SIZE).contains(n))
+                    .ok_or_else(|| format!("--batch-size must be 1..={MAX_IMPORT_SIZE}"))?,
+                None => MAX_IMPORT_SIZE,
+            };
+
+            for batch in members.chunks(batch_size) {
+                let entries: Vec<Value> = batch
+                    .iter()
+                    .map(|m| json!([m.address, m.name, m.ipfs]))
+                    .collect();
+                println!(
+                    "stellar contract invoke --id {contract} --source {source} --network {network} -- import_voters --entries {}",
+                    shell_quote(&Value::Array(entries).to_string())
+                );
+            }
+        }
+        "merkle" => {
+            let out = options.get("out").map_or("proofs.json", String::as_str);
+            let leaves: Vec<[u8; 32]> = members.iter().map(|m| sha256(&m.xdr)).collect();
+            let (root, proofs) = merkle_tree(&leaves);
+
+            let mut by_address = BTreeMap::new();
+            for (m, proof) in members.iter().zip(proofs) {
+                by_address.insert(
+                    m.address.clone(),
+                    json!({
+                        "name": m.name,
+                        "ipfs": m.ipfs,
+                        "proof": proof.iter().map(hex::encode).collect::<Vec<_>>(),
+                    }),
+                );
+            }
+
+            let file = json!({ "root": hex::encode(root), "members": by_address });
+            let pretty = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+            fs::write(out, pretty).map_err(|e| format!("{out}: {e}"))?;
+            println!("{}", hex::encode(root));
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+fn parse_options(args: &[String]) -> Result<BTreeMap<String, String>, String> {
+    let mut options = BTreeMap::new();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let key = flag
+            .strip_prefix("--")
+            .ok_or_else(|| format!("unexpected argument {flag}\n{USAGE}"))?;
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        options.insert(key.to_string(), value.clone());
+    }
+    Ok(options)
+}
+
+/// Reads and validates the whole roll, reporting every bad row at once.
+fn read_roll(path: &str) -> Result<Vec<Member>, String> {
+    let mut reader = csv::Reader::from_path(path).map_err(|e| format!("{path}: {e}"))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("{path}: {e}"))?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("{path}: missing `{name}` column"))
+    };
+    let (address_col, name_col, ipfs_col) = (column("address")?, column("name")?, column("ipfs")?);
+
+    let mut members = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (i, record) in reader.records().enumerate() {
+        // +2: one for the header row, one because lines are 1-based.
+        let line = i + 2;
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                errors.push(format!("line {line}: {e}"));
+                continue;
+            }
+        };
+        let field = |col: usize| record.get(col).unwrap_or("").trim().to_string();
+        let (address, name, ipfs) = (field(address_col), field(name_col), field(ipfs_col));
+
+        let xdr = match address_xdr(&address) {
+            Ok(xdr) => xdr,
+            Err(e) => {
+                errors.push(format!("line {line}: {e}"));
+                continue;
+            }
+        };
+        if name.is_empty() {
+            errors.push(format!("line {line}: name is empty"));
+            continue;
+        }
+        if name.len() > MAX_NAME_LEN {
+            errors.push(format!(
+                "line {line}: name is longer than {MAX_NAME_LEN} bytes"
+            ));
+            continue;
+        }
+        if !is_cid(&ipfs) {
+            errors.push(format!("line {line}: ipfs {ipfs:?} is not a CID"));
+            continue;
+        }
+        if !seen.insert(address.clone()) {
+            errors.push(format!("line {line}: duplicate address {address}"));
+            continue;
+        }
+
+        members.push(Member {
+            address,
+            xdr,
+            name,
+            ipfs,
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    if members.is_empty() {
+        return Err(format!("{path}: no members"));
+    }
+
+    Ok(members)
+}
+
+/// XDR of the address as an `ScVal`, which is what the contract hashes for
+/// a Merkle leaf.
+fn address_xdr(address: &str) -> Result<Vec<u8>, String> {
+    let sc_address = match Strkey::from_string(address) {
+        Ok(Strkey::PublicKeyEd25519(key)) => {
+            ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key.0))))
+        }
+        Ok(Strkey::Contract(contract)) => ScAddress::Contract(Hash(contract.0)),
+        Ok(_) => return Err(format!("{address} is not an account or contract address")),
+        Err(_) => return Err(format!("{address} is not a valid strkey")),
+    };
+
+    ScVal::Address(sc_address)
+        .to_xdr(Limits::none())
+        .map_err(|e| e.to_string())
+}
+
+/// Same CIDv0/CIDv1 check the contract applies to `ipfs`.
+fn is_cid(value: &str) -> bool {
+    const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    if value.len() > MAX_CID_LEN {
+        return false;
+    }
+    if let Some(rest) = value.strip_prefix("Qm") {
+        return value.len() == 46 && rest.chars().all(|c| BASE58.contains(c));
+    }
+    if let Some(rest) = value.strip_prefix("ba") {
+        return value.len() >= 8
+            && rest
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c));
+    }
+    false
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Builds the tree bottom-up with sorted-pair hashing, as verified by the
+/// contract. A node left without a sibling moves up a level unchanged.
+fn merkle_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut proofs = vec![Vec::new(); leaves.len()];
+    // Which leaves sit under each node of the current level.
+    let mut level: Vec<([u8; 32], Vec<usize>)> = leaves
+        .iter()
+        .enumerate()
+        .map(|(i, l)| (*l, vec![i]))
+        .collect();
+
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity(level.len().div_ceil(2));
+        let mut nodes = level.into_iter();
+        while let Some((left, mut under)) = nodes.next() {
+            match nodes.next() {
+                Some((right, right_under)) => {
+                    for &i in &under {
+                        proofs[i].push(right);
+                    }
+                    for &i in &right_under {
+                        proofs[i].push(left);
+                    }
+                    let (a, b) = if left < right {
+                        (left, right)
+                    } else {
+                        (right, left)
+                    };
+                    under.extend(right_under);
+                    next.push((sha256(&[a, b].concat()), under));
+                }
+                None => next.push((left, under)),
+            }
+        }
+        level = next;
+    }
+
+    (level[0].0, proofs)
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use stellar_strkey::{ed25519, Contract};
+
+    use super::*;
+
+    const CID_V0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+    const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| sha256(&[i])).collect()
+    }
+
+    /// The contract's `eligibility::verify_proof`: hash each sibling in,
+    /// smaller node first.
+    fn verify(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+        let node = proof.iter().fold(leaf, |node, sibling| {
+            if node < *sibling {
+                sha256(&[node, *sibling].concat())
+            } else {
+                sha256(&[*sibling, node].concat())
+            }
+        });
+        node == *root
+    }
+
+    #[test]
+    fn a_single_leaf_is_the_root() {
+        let leaves = leaves(1);
+        let (root, proofs) = merkle_tree(&leaves);
+
+        assert_eq!(root, leaves[0]);
+        assert_eq!(proofs, vec![Vec::<[u8; 32]>::new()]);
+        assert!(verify(&root, leaves[0], &proofs[0]));
+    }
+
+    #[test]
+    fn two_leaves_prove_each_other() {
+        let leaves = leaves(2);
+        let (root, proofs) = merkle_tree(&leaves);
+
+        assert_eq!(proofs, vec![vec![leaves[1]], vec![leaves[0]]]);
+        for (leaf, proof) in leaves.iter().zip(&proofs) {
+            assert!(verify(&root, *leaf, proof));
+        }
+    }
+
+    #[test]
+    fn every_proof_of_an_odd_roll_verifies() {
+        for n in [3, 5, 7, 9, 25] {
+            let leaves = leaves(n);
+            let (root, proofs) = merkle_tree(&leaves);
+
+            for (i, (leaf, proof)) in leaves.iter().zip(&proofs).enumerate() {
+                assert!(verify(&root, *leaf, proof), "leaf {i} of {n}");
+                let other = leaves[(i + 1) % leaves.len()];
+                assert!(!verify(&root, other, proof), "leaf {i} of {n}");
+            }
+        }
+
+        // The third leaf has no sibling on the first level, so it is
+        // promoted and only meets the pair above.
+        let leaves = leaves(3);
+        let (_, proofs) = merkle_tree(&leaves);
+        assert_eq!(proofs[2].len(), 1);
+        assert_eq!(proofs[0].len(), 2);
+    }
+
+    #[test]
+    fn is_cid_accepts_cid_v0_and_v1() {
+        assert!(is_cid(CID_V0));
+        assert!(is_cid(CID_V1));
+        assert!(is_cid("bafkqaaa"));
+    }
+
+    #[test]
+    fn is_cid_rejects_what_the_contract_rejects() {
+        let too_long = format!("ba{}", "a".repeat(MAX_CID_LEN - 1));
+        let rejected = [
+            "",
+            "NotFound",
+            "https://ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            "/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            // CIDv0 one character short and one too long.
+            &CID_V0[..45],
+            &format!("{CID_V0}a"),
+            // Characters base58btc leaves out.
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdO",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdI",
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdl",
+            // CIDv1 too short, in the wrong case, or outside base32.
+            "bafkqaa",
+            "BAFYBEIGDYRZT5SFP7UDM7HU76UH7Y26NF3EFUYLQABF3OCLGTQY55FBZDI",
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzd1",
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzd8",
+            // Other multibase prefixes.
+            "zdj7WWeQ43G6JJvLWQWZpyHuAMq6uYWRjkBXFad11vE2LHhQ7",
+            "bcfybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            &too_long,
+        ];
+        for value in rejected {
+            assert!(!is_cid(value), "{value:?}");
+        }
+        assert!(is_cid(&too_long[..MAX_CID_LEN]));
+    }
+
+    #[test]
+    fn accounts_and_contracts_are_addresses() {
+        let account = ed25519::PublicKey([7; 32]).to_string();
+        let contract = Contract([7; 32]).to_string();
+
+        assert!(address_xdr(&account).is_ok());
+        assert!(address_xdr(&contract).is_ok());
+        assert_ne!(address_xdr(&account), address_xdr(&contract));
+    }
+
+    #[test]
+    fn malformed_and_secret_strkeys_are_rejected() {
+        let account = ed25519::PublicKey([7; 32]).to_string();
+        let mut bad_checksum = account.clone();
+        let last = if account.ends_with('A') { "B" } else { "A" };
+        bad_checksum.replace_range(account.len() - 1.., last);
+        let seed = ed25519::PrivateKey([7; 32]).to_string();
+
+        for value in [
+            String::new(),
+            "not an address".to_string(),
+            account[..account.len() - 1].to_string(),
+            format!(" {account}"),
+            bad_checksum,
+        ] {
+            let Err(error) = address_xdr(&value) else {
+                panic!("accepted {value:?}")
+            };
+            assert!(
+                error.ends_with("is not a valid strkey"),
+                "{value:?}: {error}"
+            );
+        }
+
+        let error = address_xdr(&seed).unwrap_err();
+        assert!(error.ends_with("is not an account or contract address"));
+    }
+}